@@ -1,74 +1,175 @@
-use std::{collections::BTreeSet, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use iced::{
     Background, Border, Color, Element, Font, Length, Subscription, Task, border,
+    futures::channel::mpsc,
     widget::{
-        Space, button, center, column, container, mouse_area, opaque, row, scrollable, stack, svg,
-        text,
+        Space, button, center, checkbox, column, container, mouse_area, opaque, row, scrollable,
+        stack, svg, text, text_input,
     },
 };
-use windows::Win32::System::Threading::TerminateProcess;
 
-use crate::{
-    KOREAN_FONT, SCRStruct,
-    scr::{StringExt, get_owned_handle, get_path, process_handles, query_child, run_scr, save_log},
-    settings::Settings,
+use scr_multi_launcher::{
+    APP_NAME, Event, InstanceId, SCRStruct, Settings, State,
+    batch::{BatchLaunch, BatchProgress, BatchStep, run_batch},
+    battle_net::{self, PendingLaunch},
+    discovery::{Discovery, Installation},
+    fingerprint::{self, Check, Fingerprint},
+    game::GameDefinition,
+    pe::{self, PeInfo, Version},
+    scr::{
+        ProcessBackend, StringExt,
+        cmdline::{join_args, split_args},
+        get_path, open_instance, poll, save_log,
+    },
+    settings::{Launcher, Profile, WineSettings},
 };
 
+use crate::KOREAN_FONT;
+
 const CHECK_INTERVAL: Duration = Duration::from_millis(500);
 const GEAR: &[u8] = include_bytes!("../assets/gear-svgrepo-com.svg");
 const SEARCH: &[u8] = include_bytes!("../assets/search-svgrepo-com.svg");
+const MAX_BATCH_COUNT: u32 = 16;
+const WARNING_COLOR: Color = Color::from_rgb(0.8, 0.4, 0.0);
+const ERROR_COLOR: Color = Color::from_rgb(0.8, 0.0, 0.0);
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Tick,
 
-    ProcessCheckResult(Vec<SCRStruct>, Vec<String>),
-    ProcessLog(Option<String>),
+    // 새로 나타난 인스턴스, 인스턴스별로 일어난 일
+    ProcessCheckResult(Vec<SCRStruct>, Vec<(InstanceId, Event)>),
+    CheckExecutables,
+    AcknowledgeUpdates,
     KillAll,
-    RunSCR(String),
+    RunSCR(Profile),
+    // 실행 전에 확인한 실행 파일 지문
+    ExecutableChecked(Profile, Result<Check, String>),
+    // 시작할 때 확인한 프로필 실행 파일들의 지문
+    ExecutablesChecked(Vec<(String, Result<Check, String>)>),
+    SetBatchCount(u32),
+    BatchProgress(BatchProgress),
+    BatchFinished,
+    CancelBatch,
     SaveLogs,
     ClearLogs,
     OpenSettings,
+    // 설정 창을 열 때 찾은 설치
+    InstallationsFound(Vec<Installation>),
     CmdResult(Result<(), String>),
 
     // 설정 다이얼로그 메시지
-    OpenFolderDialog32,
-    OpenFolderDialog64,
+    AddProfile,
+    AddInstallation(usize),
+    RemoveProfile(usize),
+    ProfileNameChanged(usize, String),
+    ProfileArgsChanged(usize, String),
+    ProfileLauncherToggled(usize, bool),
+    OpenFolderDialog(usize),
+    // 설정 창에서 고른 실행 파일과 그 헤더
+    ExecutableSelected(usize, String, Result<PeInfo, String>),
+    BatchDelayChanged(String),
+    BattleNetPathChanged(String),
+    BlockOnUpdateToggled(bool),
     SaveSettings,
     SaveSettingsResult(Result<Settings, String>),
     CloseSettings,
 }
 
-pub struct App {
+pub struct App<B: ProcessBackend> {
+    backend: B,
     is_timer_on: bool,
     show_settings: bool,
     childs: BTreeSet<SCRStruct>,
     logs: BTreeSet<String>,
     settings: Settings,
     temp_settings: Settings,
+    // 실행 파일 경로별 PE 헤더
+    executables: BTreeMap<String, Result<PeInfo, String>>,
+    // 설정 창에서 고른 파일을 쓸 수 없을 때
+    settings_error: Option<String>,
+    // 설정 창을 열 때 찾은 StarCraft 설치
+    installations: Vec<Installation>,
+    // 입력 중인 인자 칸의 프로필 번호와 글자
+    //
+    // 나눈 인자로는 입력 중인 공백과 따옴표를 되살릴 수 없으므로 입력하는 동안은 글자를 그대로 보여 줌
+    args_input: Option<(usize, String)>,
+    // 확인을 기다리는 업데이트된 실행 파일의 새 지문
+    pending_updates: BTreeMap<String, Fingerprint>,
+    // StarCraft.exe가 나타나기를 기다리는 Battle.net 실행
+    pending_launches: Vec<PendingLaunch>,
+    // 인스턴스별로 열어 둔 프로세스, 끝난 뒤 종료 코드를 읽기 위해 유지
+    processes: Arc<Mutex<BTreeMap<InstanceId, B::Handle>>>,
+    // 이전 Tick의 확인이 끝나지 않았으면 다음 Tick을 건너뜀
+    is_checking: bool,
+    batch_count: u32,
+    // 일괄 실행 중일 때만 있음
+    batch_cancel: Option<Arc<AtomicBool>>,
 }
 
-impl App {
-    pub fn new(maybe_settings: Option<Settings>) -> Self {
+impl<B: ProcessBackend> App<B> {
+    pub fn new(
+        mut backend: B,
+        maybe_settings: Option<Settings>,
+        maybe_game: Result<Option<GameDefinition>, String>,
+    ) -> Self {
         let childs = BTreeSet::new();
         let mut logs = BTreeSet::new();
+        match maybe_game {
+            Ok(maybe_game) => backend.set_game(maybe_game.unwrap_or_default()),
+            Err(err) => {
+                logs.insert(format!("game.toml 파일이 손상되었습니다: {err}").as_log());
+            }
+        }
         let settings = if let Some(settings) = maybe_settings {
             settings
         } else {
             logs.insert("conf.toml 파일이 없거나 손상되었습니다.".as_log());
-            Settings::default()
+            // Battle.net이 기록한 설치 위치로 프로필을 채움
+            let settings = Discovery::system(&WineSettings::default()).default_settings();
+            for profile in &settings.profiles {
+                logs.insert(format!("product.db에서 찾은 설치: {}", profile.path).as_log());
+            }
+            settings
         };
         let temp_settings = settings.clone();
+        backend.configure(&settings);
 
-        Self {
+        let mut app = Self {
+            backend,
             is_timer_on: true,
             show_settings: false,
             childs,
             settings,
             temp_settings,
+            executables: BTreeMap::new(),
+            settings_error: None,
+            installations: Vec::new(),
+            args_input: None,
+            pending_updates: BTreeMap::new(),
+            pending_launches: Vec::new(),
+            processes: Arc::default(),
+            is_checking: false,
             logs,
-        }
+            batch_count: 1,
+            batch_cancel: None,
+        };
+        app.refresh_executables();
+
+        app
+    }
+
+    pub fn title(&self) -> String {
+        format!("{APP_NAME} - {}", self.backend.game().display_name)
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
@@ -78,40 +179,62 @@ impl App {
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Tick => {
-                let childs = std::mem::take(&mut self.childs);
+                if self.is_checking {
+                    return Task::none();
+                }
+                self.is_checking = true;
+                let childs = self.childs.clone();
+                let processes = self.processes.clone();
+                let backend = self.backend.clone();
                 Task::perform(
                     async move {
-                        let mut new_childs = process_handles();
-                        let mut logs = Vec::new();
-                        new_childs.extend(childs);
-
-                        new_childs.retain_mut(|child| {
-                            if child.is_processed {
-                                if get_owned_handle(child.pid).is_none() {
-                                    logs.push(format!("Invalid PID: {}", child.pid).as_log());
-
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else if let Some(log) = query_child(child.pid, None) {
-                                child.is_processed = true;
-                                logs.push(log);
-
-                                true
-                            } else {
-                                true
-                            }
-                        });
-
-                        (new_childs, logs)
+                        let found = backend.enumerate();
+                        let running: BTreeSet<_> = found.iter().map(SCRStruct::id).collect();
+                        let appeared: Vec<_> = found
+                            .into_iter()
+                            .filter(|child| !childs.contains(child))
+                            .collect();
+                        let events = appeared
+                            .iter()
+                            .chain(&childs)
+                            .flat_map(|child| {
+                                let running = running.contains(&child.id());
+                                poll(&backend, child, running, &processes)
+                                    .into_iter()
+                                    .map(|event| (child.id(), event))
+                            })
+                            .collect();
+
+                        (appeared, events)
                     },
-                    |(new_childs, logs)| Message::ProcessCheckResult(new_childs, logs),
+                    |(appeared, events)| Message::ProcessCheckResult(appeared, events),
                 )
             }
-            Message::ProcessCheckResult(new_childs, logs) => {
-                self.childs.extend(new_childs);
-                self.logs.extend(logs);
+            Message::ProcessCheckResult(appeared, events) => {
+                self.is_checking = false;
+                // 실행 중에 먼저 추가된 인스턴스는 그대로 둠
+                self.childs.extend(appeared.iter().cloned());
+                for (id, event) in events {
+                    if self.record(id, &event) {
+                        self.logs
+                            .insert(event.describe(self.backend.game()).as_log());
+                    }
+                }
+                self.childs.retain(|child| !child.is_finished());
+                // 확인하는 사이 종료한 인스턴스의 핸들을 Tick이 다시 넣었을 수 있음
+                let ids: BTreeSet<_> = self.childs.iter().map(SCRStruct::id).collect();
+                self.processes
+                    .lock()
+                    .unwrap()
+                    .retain(|id, _| ids.contains(id));
+                let handoffs = battle_net::correlate(
+                    &self.backend,
+                    &mut self.pending_launches,
+                    &appeared,
+                    Instant::now(),
+                );
+                self.logs
+                    .extend(handoffs.iter().map(|handoff| handoff.to_string().as_log()));
                 if !self.is_timer_on {
                     self.kill_childs();
                     self.is_timer_on = true;
@@ -119,45 +242,234 @@ impl App {
 
                 Task::none()
             }
-            Message::RunSCR(path) => Task::perform(
-                async move {
-                    if let Some((pid, handle)) = run_scr(&path, &["-launch"]) {
-                        let log = query_child(pid, Some(handle));
+            Message::CheckExecutables => {
+                let checks: Vec<_> = self
+                    .profile_paths()
+                    .into_iter()
+                    .filter(|path| !self.pending_updates.contains_key(path))
+                    .map(|path| {
+                        let stored = self.settings.fingerprints.get(&path).cloned();
+                        (path, stored)
+                    })
+                    .collect();
 
-                        log
-                    } else {
-                        None
+                // 해시 계산은 오래 걸릴 수 있으므로 UI 스레드 밖에서 함
+                Task::perform(
+                    async move {
+                        checks
+                            .into_iter()
+                            .map(|(path, stored)| {
+                                let result = fingerprint::check(&path, stored.as_ref());
+                                (path, result)
+                            })
+                            .collect()
+                    },
+                    Message::ExecutablesChecked,
+                )
+            }
+            Message::ExecutablesChecked(results) => {
+                let paths = self.profile_paths();
+                let count = self.settings.fingerprints.len();
+                self.settings
+                    .fingerprints
+                    .retain(|path, _| paths.contains(path));
+                let mut changed = self.settings.fingerprints.len() != count;
+                for (path, result) in results {
+                    changed |= self.apply_check(&path, result);
+                }
+
+                if changed {
+                    self.save_fingerprints()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::AcknowledgeUpdates => {
+                let updates = std::mem::take(&mut self.pending_updates);
+                if updates.is_empty() {
+                    return Task::none();
+                }
+                self.settings.fingerprints.extend(updates);
+
+                self.save_fingerprints()
+            }
+            Message::RunSCR(profile) => {
+                if self.batch_cancel.is_some() {
+                    return Task::none();
+                }
+                if profile.path.is_empty() || self.pending_updates.contains_key(&profile.path) {
+                    return self.launch(profile);
+                }
+                // 해시 계산은 오래 걸릴 수 있으므로 UI 스레드 밖에서 하고 끝나면 실행
+                let stored = self.settings.fingerprints.get(&profile.path).cloned();
+
+                Task::perform(
+                    async move {
+                        let result = fingerprint::check(&profile.path, stored.as_ref());
+                        (profile, result)
+                    },
+                    |(profile, result)| Message::ExecutableChecked(profile, result),
+                )
+            }
+            Message::ExecutableChecked(profile, result) => {
+                let save = if self.apply_check(&profile.path, result) {
+                    self.save_fingerprints()
+                } else {
+                    Task::none()
+                };
+
+                Task::batch([save, self.launch(profile)])
+            }
+            Message::SetBatchCount(count) => {
+                self.batch_count = count.clamp(1, MAX_BATCH_COUNT);
+
+                Task::none()
+            }
+            Message::BatchProgress(progress) => {
+                // 일괄 실행 로그는 항상 남기고 인스턴스 상태만 맞춤
+                match &progress.step {
+                    BatchStep::Launched {
+                        pid,
+                        created,
+                        version,
+                    } => {
+                        let event = Event::Launched {
+                            pid: *pid,
+                            version: *version,
+                        };
+                        self.record((*pid, *created), &event);
                     }
-                },
-                |maybe_log| Message::ProcessLog(maybe_log),
-            ),
+                    BatchStep::Ready(event) => {
+                        // 일괄 실행이 방금 실행한 인스턴스
+                        let launched = self
+                            .childs
+                            .iter()
+                            .find(|child| child.pid == event.pid() && !child.is_finished())
+                            .map(SCRStruct::id);
+                        if let Some(id) = launched {
+                            self.record(id, event);
+                        }
+                    }
+                    _ => {}
+                }
+                self.logs
+                    .insert(progress.describe(self.backend.game()).as_log());
+
+                Task::none()
+            }
+            Message::BatchFinished => {
+                self.batch_cancel = None;
+
+                Task::none()
+            }
+            Message::CancelBatch => {
+                if let Some(cancel) = &self.batch_cancel {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+
+                Task::none()
+            }
             Message::KillAll => {
                 self.is_timer_on = false;
                 self.kill_childs();
 
                 Task::none()
             }
-            Message::ProcessLog(maybe_log) => {
-                if let Some(log) = maybe_log {
-                    self.logs.insert(log.as_log());
+            Message::AddProfile => {
+                let name = format!("Profile {}", self.temp_settings.profiles.len() + 1);
+                self.temp_settings.profiles.push(Profile {
+                    args: self.backend.game().default_args.clone(),
+                    ..Profile::new(name, "")
+                });
+
+                Task::none()
+            }
+            Message::AddInstallation(index) => {
+                if let Some(installation) = self.installations.get(index) {
+                    let mut profile = installation.profile();
+                    profile.name = unique_name(&self.temp_settings.profiles, &profile.name);
+                    self.executables
+                        .insert(profile.path.clone(), pe::inspect(&profile.path));
+                    self.temp_settings.profiles.push(profile);
                 }
 
                 Task::none()
             }
-            Message::OpenFolderDialog32 => {
-                if let Some(path_32) = get_path() {
-                    self.temp_settings.path_32 = path_32;
+            Message::RemoveProfile(index) => {
+                if index < self.temp_settings.profiles.len() {
+                    self.temp_settings.profiles.remove(index);
+                    self.args_input = None;
                 }
 
                 Task::none()
             }
-            Message::OpenFolderDialog64 => {
-                if let Some(path_64) = get_path() {
-                    self.temp_settings.path_64 = path_64;
+            Message::ProfileNameChanged(index, name) => {
+                if let Some(profile) = self.temp_settings.profiles.get_mut(index) {
+                    profile.name = name;
                 }
 
                 Task::none()
             }
+            Message::ProfileArgsChanged(index, args) => {
+                // 공백이 있는 인자는 명령줄처럼 따옴표로 감쌈
+                if let Some(profile) = self.temp_settings.profiles.get_mut(index) {
+                    profile.args = split_args(&args)
+                        .into_iter()
+                        .filter(|arg| !arg.is_empty())
+                        .collect();
+                    self.args_input = Some((index, args));
+                }
+
+                Task::none()
+            }
+            Message::ProfileLauncherToggled(index, battle_net) => {
+                if let Some(profile) = self.temp_settings.profiles.get_mut(index) {
+                    profile.launcher = if battle_net {
+                        Launcher::BattleNet
+                    } else {
+                        Launcher::Direct
+                    };
+                }
+
+                Task::none()
+            }
+            Message::OpenFolderDialog(index) => match get_path() {
+                Some(path) => self.select_executable(index, path),
+                None => Task::none(),
+            },
+            Message::ExecutableSelected(index, path, info) => {
+                match info {
+                    Ok(info) => {
+                        if let Some(profile) = self.temp_settings.profiles.get_mut(index) {
+                            profile.path = path.clone();
+                        }
+                        self.executables.insert(path, Ok(info));
+                        self.settings_error = None;
+                    }
+                    Err(err) => self.settings_error = Some(format!("{path}: {err}")),
+                }
+
+                Task::none()
+            }
+            Message::BatchDelayChanged(delay) => {
+                if delay.is_empty() {
+                    self.temp_settings.batch_delay = 0;
+                } else if let Ok(delay) = delay.parse() {
+                    self.temp_settings.batch_delay = delay;
+                }
+
+                Task::none()
+            }
+            Message::BattleNetPathChanged(path) => {
+                self.temp_settings.battle_net = path;
+
+                Task::none()
+            }
+            Message::BlockOnUpdateToggled(block) => {
+                self.temp_settings.block_on_update = block;
+
+                Task::none()
+            }
             Message::SaveLogs => {
                 let logs = std::mem::take(&mut self.logs);
                 Task::perform(save_log(logs), Message::CmdResult)
@@ -171,8 +483,26 @@ impl App {
             Message::OpenSettings => {
                 self.show_settings = true;
                 self.temp_settings = self.settings.clone();
+                self.settings_error = None;
+                self.args_input = None;
+                // 게임이 업데이트되었을 수 있으므로 다시 읽음
+                self.refresh_executables();
+                self.installations.clear();
+                // 레지스트리와 폴더를 뒤지므로 UI 스레드 밖에서 찾음
+                let wine = self.settings.wine.clone();
+
+                Task::batch([
+                    iced::widget::focus_next(),
+                    Task::perform(
+                        async move { Discovery::system(&wine).find() },
+                        Message::InstallationsFound,
+                    ),
+                ])
+            }
+            Message::InstallationsFound(installations) => {
+                self.installations = installations;
 
-                iced::widget::focus_next()
+                Task::none()
             }
             Message::SaveSettings => {
                 self.show_settings = false;
@@ -184,7 +514,11 @@ impl App {
             }
             Message::SaveSettingsResult(result) => {
                 match result {
-                    Ok(settings) => self.settings = settings,
+                    Ok(settings) => {
+                        self.backend.configure(&settings);
+                        self.settings = settings;
+                        self.refresh_executables();
+                    }
                     Err(err) => {
                         self.logs.insert(err.as_log());
                     }
@@ -205,50 +539,208 @@ impl App {
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         let content = self.main_view();
         if self.show_settings {
             modal(content, self.settings_view(), Message::CloseSettings)
         } else {
-            content.into()
+            content
         }
     }
 
-    fn kill_childs(&mut self) -> () {
-        self.childs.retain(|child| {
-            if let Some(handle) = get_owned_handle(child.pid) {
-                match unsafe { TerminateProcess(*handle, 0) } {
-                    Ok(_) => {
-                        self.logs.insert(
-                            format!("Successfully terminated process wid PID {}", child.pid)
-                                .as_log(),
-                        );
-                        false
-                    }
-                    _ => true,
-                }
-            } else {
-                false
+    // 저장된 설정과 편집 중인 설정의 실행 파일을 모두 다시 읽음
+    fn refresh_executables(&mut self) {
+        self.executables.clear();
+        let profiles = self.settings.profiles.iter();
+        for profile in profiles.chain(&self.temp_settings.profiles) {
+            if !profile.path.is_empty() && !self.executables.contains_key(&profile.path) {
+                self.executables
+                    .insert(profile.path.clone(), pe::inspect(&profile.path));
             }
+        }
+    }
+
+    // 지문 확인이 끝난 프로필을 실행, 업데이트를 확인해야 하면 막음
+    fn launch(&mut self, profile: Profile) -> Task<Message> {
+        // 지문을 확인하는 사이 다른 일괄 실행이 시작되었을 수 있음
+        if self.batch_cancel.is_some() {
+            return Task::none();
+        }
+        if self.pending_updates.contains_key(&profile.path) {
+            self.logs.insert(
+                format!(
+                    "Launch of {} is blocked until the update is acknowledged",
+                    profile.name
+                )
+                .as_log(),
+            );
+            return Task::none();
+        }
+        // Battle.net이 실행한 클라이언트는 Tick에서 찾아 뮤텍스를 닫음
+        if profile.launcher == Launcher::BattleNet {
+            self.launch_battle_net(&profile);
+            return Task::none();
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.batch_cancel = Some(cancel.clone());
+        let batch = BatchLaunch {
+            delay: Duration::from_millis(self.settings.batch_delay),
+            ..BatchLaunch::new(profile, self.batch_count)
+        };
+        let backend = self.backend.clone();
+        let (sender, receiver) = mpsc::unbounded();
+        // 뮤텍스를 기다리는 동안 블로킹되므로 별도 스레드에서 실행
+        thread::spawn(move || {
+            run_batch(&backend, &batch, &cancel, |progress| {
+                let _ = sender.unbounded_send(progress);
+            })
         });
+
+        Task::run(receiver, Message::BatchProgress).chain(Task::done(Message::BatchFinished))
     }
 
-    fn main_view(&self) -> Element<Message> {
-        let run_32 = if self.settings.path_32.is_empty() {
-            button("32bit").padding([8, 24])
-        } else {
-            button("32bit")
-                .on_press(Message::RunSCR(self.settings.path_32.clone()))
-                .padding([8, 24])
+    // 저장된 프로필의 실행 파일 경로
+    fn profile_paths(&self) -> BTreeSet<String> {
+        self.settings
+            .profiles
+            .iter()
+            .filter(|profile| !profile.path.is_empty())
+            .map(|profile| profile.path.clone())
+            .collect()
+    }
+
+    // 지문을 비교한 결과를 반영, 저장할 지문이 바뀌었으면 true
+    //
+    // 업데이트를 확인해야 하면 지문은 그대로 두고 확인을 기다린다.
+    fn apply_check(&mut self, path: &str, result: Result<Check, String>) -> bool {
+        match result {
+            Ok(Check::Unchanged) | Err(_) => false,
+            Ok(Check::Recorded(current)) => {
+                self.settings.fingerprints.insert(path.to_string(), current);
+                true
+            }
+            Ok(Check::Updated { previous, current }) => {
+                self.logs.insert(
+                    format!(
+                        "{} was updated ({} -> {}): {path}. The mutex name may have changed",
+                        self.backend.game().display_name,
+                        previous.label(),
+                        current.label()
+                    )
+                    .as_log(),
+                );
+                if self.settings.block_on_update {
+                    self.pending_updates.insert(path.to_string(), current);
+                    false
+                } else {
+                    self.settings.fingerprints.insert(path.to_string(), current);
+                    true
+                }
+            }
+        }
+    }
+
+    // Battle.net을 실행하고 StarCraft.exe가 나타나기를 기다림
+    fn launch_battle_net(&mut self, profile: &Profile) {
+        let request = profile.battle_net_request(self.settings.battle_net_path());
+        let log = match self.backend.spawn(&request) {
+            Some((pid, _)) => {
+                self.pending_launches
+                    .push(PendingLaunch::new(&profile.name, pid));
+                format!(
+                    "Started Battle.net.exe (PID: {pid}) to launch {}",
+                    profile.name
+                )
+            }
+            None => format!("Failed to start {}", request.path),
         };
+        self.logs.insert(log.as_log());
+    }
 
-        let run_64 = if self.settings.path_64.is_empty() {
-            button("64bit").padding([8, 24])
-        } else {
-            button("64bit")
-                .on_press(Message::RunSCR(self.settings.path_32.clone()))
-                .padding([8, 24])
+    // 설정 창에서 편집 중인 내용과 섞이지 않게 저장된 설정만 다시 씀
+    fn save_fingerprints(&mut self) -> Task<Message> {
+        self.temp_settings.fingerprints = self.settings.fingerprints.clone();
+
+        Task::perform(self.settings.clone().save(), |result| {
+            Message::CmdResult(result.map(|_| ()))
+        })
+    }
+
+    // 아직 프로필에 없는 찾은 설치
+    fn installation_rows(&self) -> impl Iterator<Item = Element<'_, Message>> {
+        self.installations
+            .iter()
+            .enumerate()
+            .filter(|(_, installation)| {
+                self.temp_settings
+                    .profiles
+                    .iter()
+                    .all(|profile| profile.path != installation.path)
+            })
+            .map(|(index, installation)| installation_row(index, installation))
+    }
+
+    fn version(&self, profile: &Profile) -> Option<Version> {
+        self.executables
+            .get(&profile.path)?
+            .as_ref()
+            .ok()
+            .and_then(PeInfo::version)
+    }
+
+    // 실행 파일 전체를 읽으므로 UI 스레드 밖에서 확인, PE 실행 파일이 아니면 경로를 바꾸지 않음
+    fn select_executable(&self, index: usize, path: String) -> Task<Message> {
+        Task::perform(
+            async move {
+                let info = pe::inspect(&path);
+                (path, info)
+            },
+            move |(path, info)| Message::ExecutableSelected(index, path, info),
+        )
+    }
+
+    // `event`로 인스턴스 상태를 바꾸고, 바뀌었으면 true
+    //
+    // 찾거나 실행한 인스턴스만 새로 추적하므로 이미 정리된 인스턴스의 늦은 사건은 무시된다.
+    fn record(&mut self, (pid, created): InstanceId, event: &Event) -> bool {
+        let mut key = SCRStruct::new(pid);
+        key.created = created;
+        let mut child = match self.childs.take(&key) {
+            Some(child) => child,
+            None if matches!(event, Event::Launched { .. } | Event::Found { .. }) => key,
+            None => return false,
         };
+        let changed = child.apply(event);
+        self.childs.insert(child);
+
+        changed
+    }
+
+    fn kill_childs(&mut self) {
+        let mut processes = self.processes.lock().unwrap();
+        let mut terminated = Vec::new();
+        for child in &self.childs {
+            // PID가 재사용된 다른 프로세스는 종료하지 않음
+            let opened = processes
+                .remove(&child.id())
+                .or_else(|| open_instance(&self.backend, child));
+            if opened.is_some_and(|process| self.backend.terminate(&process)) {
+                terminated.push((child.id(), Event::Terminated { pid: child.pid }));
+            }
+        }
+        drop(processes);
+
+        for (id, event) in terminated {
+            if self.record(id, &event) {
+                self.logs
+                    .insert(event.describe(self.backend.game()).as_log());
+            }
+        }
+        self.childs.retain(|child| !child.is_finished());
+    }
+
+    fn main_view(&self) -> Element<'_, Message> {
+        let is_batch_running = self.batch_cancel.is_some();
 
         // 상단 버튼 행
         let top_row = row![
@@ -262,16 +754,48 @@ impl App {
             .padding(2)
             .on_press(Message::OpenSettings),
             Space::with_width(Length::Fill),
-            run_32,
-            Space::with_width(Length::Fixed(12.0)),
-            run_64,
-            Space::with_width(Length::Fixed(12.0)),
             button("Kill All")
                 .on_press(Message::KillAll)
                 .padding([8, 16]),
         ]
         .align_y(iced::Alignment::Center);
 
+        // 프로필 버튼 행
+        let profile_row: Element<'_, Message> =
+            if self.settings.profiles.is_empty() {
+                text("설정에서 프로필을 추가하세요.").size(14).into()
+            } else {
+                row(self.settings.profiles.iter().map(|profile| {
+                    profile_button(profile, self.version(profile), is_batch_running)
+                }))
+                .spacing(8)
+                .wrap()
+                .into()
+            };
+
+        // 일괄 실행 행
+        let batch_row = row![
+            text("Count").size(14),
+            Space::with_width(Length::Fixed(8.0)),
+            button(text("-").center())
+                .on_press_maybe(
+                    (self.batch_count > 1).then_some(Message::SetBatchCount(self.batch_count - 1))
+                )
+                .width(28),
+            text(self.batch_count.to_string()).width(28).center(),
+            button(text("+").center())
+                .on_press_maybe(
+                    (self.batch_count < MAX_BATCH_COUNT)
+                        .then_some(Message::SetBatchCount(self.batch_count + 1))
+                )
+                .width(28),
+            Space::with_width(Length::Fill),
+            button("Cancel")
+                .on_press_maybe(is_batch_running.then_some(Message::CancelBatch))
+                .padding([4, 16]),
+        ]
+        .align_y(iced::Alignment::Center);
+
         // 로그 영역
         let logs_colum = column(self.logs.iter().map(|log| text(log).size(12).into()));
         let logs_area = container(scrollable(logs_colum))
@@ -301,13 +825,39 @@ impl App {
                 .padding(8),
         ];
 
-        column![top_row, logs_area, bottom_row,]
+        // 업데이트 확인 행
+        let update_row = (!self.pending_updates.is_empty()).then(|| {
+            row![
+                text("게임이 업데이트되었습니다. 확인해야 실행할 수 있습니다.")
+                    .size(12)
+                    .color(WARNING_COLOR)
+                    .width(Length::Fill),
+                button("OK")
+                    .on_press(Message::AcknowledgeUpdates)
+                    .padding([4, 16]),
+            ]
+            .align_y(iced::Alignment::Center)
+        });
+
+        // 인스턴스 상태 행
+        let instances_row = (!self.childs.is_empty()).then(|| {
+            row(self.childs.iter().filter_map(instance_label))
+                .spacing(12)
+                .wrap()
+        });
+
+        column![top_row, profile_row]
+            .push_maybe(update_row)
+            .push(batch_row)
+            .push_maybe(instances_row)
+            .push(logs_area)
+            .push(bottom_row)
             .padding(8)
             .spacing(8)
             .into()
     }
 
-    fn settings_view(&self) -> Element<Message> {
+    fn settings_view(&self) -> Element<'_, Message> {
         let dialog_content = column![
             text("설정").size(18).font(Font {
                 weight: iced::font::Weight::Bold,
@@ -315,67 +865,74 @@ impl App {
                 ..Default::default()
             }),
             Space::with_height(Length::Fixed(12.0)),
-            text("32bit").size(16).font(Font {
+            text("프로필").size(16).font(Font {
                 weight: iced::font::Weight::Bold,
                 family: KOREAN_FONT.family,
                 ..Default::default()
             }),
-            row![
-                container(text(&self.temp_settings.path_32).size(10))
-                    .style(|_| container::Style {
-                        border: Border {
-                            color: Color::BLACK,
-                            width: 1.0,
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .width(Length::Fill)
-                    .padding([8, 4]),
-                button(
-                    svg(iced::widget::svg::Handle::from_memory(SEARCH)).style(|_, _| {
-                        svg::Style {
-                            color: Some(Color::WHITE),
-                        }
-                    })
+            scrollable(
+                column(
+                    self.temp_settings
+                        .profiles
+                        .iter()
+                        .enumerate()
+                        .map(|(index, profile)| {
+                            let args = match &self.args_input {
+                                Some((editing, args)) if *editing == index => args.clone(),
+                                _ => join_args(&profile.args),
+                            };
+                            profile_editor(
+                                index,
+                                profile,
+                                &args,
+                                self.executables.get(&profile.path),
+                            )
+                        })
+                        .chain(self.installation_rows())
                 )
-                .width(36)
-                .height(36)
-                .padding(4)
-                .on_press(Message::OpenFolderDialog32),
+                .spacing(12)
+                .padding([0, 12]),
+            )
+            .height(Length::Fixed(150.0)),
+            Space::with_height(Length::Fixed(4.0)),
+            row![
+                button("프로필 추가")
+                    .on_press(Message::AddProfile)
+                    .padding([4, 12]),
+                text(self.settings_error.as_deref().unwrap_or_default())
+                    .size(10)
+                    .color(ERROR_COLOR),
             ]
-            .spacing(4)
+            .spacing(8)
             .align_y(iced::Alignment::Center),
             Space::with_height(Length::Fixed(8.0)),
-            text("64bit").font(Font {
+            text("일괄 실행 간격 (ms)").font(Font {
                 weight: iced::font::Weight::Bold,
                 family: KOREAN_FONT.family,
                 ..Default::default()
             }),
-            row![
-                container(text(&self.temp_settings.path_64).size(10))
-                    .style(|_| container::Style {
-                        border: Border {
-                            color: Color::BLACK,
-                            width: 1.0,
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .width(Length::Fill)
-                    .padding([8, 4]),
-                button(
-                    svg(iced::widget::svg::Handle::from_memory(SEARCH)).style(|_, _| svg::Style {
-                        color: Some(Color::WHITE)
-                    })
-                )
-                .width(36)
-                .height(36)
-                .padding(4)
-                .on_press(Message::OpenFolderDialog64),
-            ]
-            .spacing(4)
-            .align_y(iced::Alignment::Center),
+            text_input("0", &self.temp_settings.batch_delay.to_string())
+                .on_input(Message::BatchDelayChanged)
+                .size(12)
+                .padding([6, 4]),
+            Space::with_height(Length::Fixed(8.0)),
+            checkbox(
+                "게임이 업데이트되면 확인할 때까지 실행하지 않음",
+                self.temp_settings.block_on_update
+            )
+            .on_toggle(Message::BlockOnUpdateToggled)
+            .size(14)
+            .text_size(12),
+            Space::with_height(Length::Fixed(8.0)),
+            text("Battle.net 경로").font(Font {
+                weight: iced::font::Weight::Bold,
+                family: KOREAN_FONT.family,
+                ..Default::default()
+            }),
+            text_input(battle_net::DEFAULT_PATH, &self.temp_settings.battle_net)
+                .on_input(Message::BattleNetPathChanged)
+                .size(12)
+                .padding([6, 4]),
             Space::with_height(Length::Fixed(12.0)),
             row![
                 Space::with_width(Length::Fill),
@@ -403,6 +960,192 @@ impl App {
     }
 }
 
+// 프로필 색상을 배경으로 쓰는 실행 버튼, 버전을 알면 이름 아래에 표시
+fn profile_button(
+    profile: &Profile,
+    version: Option<Version>,
+    is_batch_running: bool,
+) -> Element<'_, Message> {
+    let color = Color::parse(&profile.color);
+    let label = column![text(&profile.name)]
+        .push_maybe(version.map(|version| text(version.to_string()).size(10)))
+        .align_x(iced::Alignment::Center);
+
+    button(label)
+        .on_press_maybe(
+            (profile.is_launchable() && !is_batch_running)
+                .then(|| Message::RunSCR(profile.clone())),
+        )
+        .padding([8, 24])
+        .style(move |theme, status| {
+            let style = button::primary(theme, status);
+            match (color, status) {
+                (_, button::Status::Disabled) | (None, _) => style,
+                (Some(color), button::Status::Hovered) => button::Style {
+                    background: Some(Color { a: 0.8, ..color }.into()),
+                    ..style
+                },
+                (Some(color), _) => button::Style {
+                    background: Some(color.into()),
+                    ..style
+                },
+            }
+        })
+        .into()
+}
+
+// 인스턴스의 지금 상태와 바뀐 시각, 예: "PID 1234: Mutex closed (12:30:01)"
+fn instance_label(child: &SCRStruct) -> Option<Element<'_, Message>> {
+    let transition = child.history().last()?;
+    let color = match transition.state {
+        State::OpenFailed => Some(ERROR_COLOR),
+        State::Launching | State::WaitingForMutex => Some(WARNING_COLOR),
+        _ => None,
+    };
+    let label = format!(
+        "PID {}: {} ({})",
+        child.pid,
+        transition.state,
+        transition.at.format("%H:%M:%S")
+    );
+
+    Some(text(label).size(12).color_maybe(color).into())
+}
+
+// 이름이 겹치면 뒤에 번호를 붙임
+fn unique_name(profiles: &[Profile], name: &str) -> String {
+    let taken = |candidate: &str| profiles.iter().any(|profile| profile.name == candidate);
+    if !taken(name) {
+        return name.to_string();
+    }
+
+    (2..)
+        .map(|number| format!("{name} {number}"))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_default()
+}
+
+// 설정 창의 찾은 설치 하나
+fn installation_row(index: usize, installation: &Installation) -> Element<'_, Message> {
+    row![
+        column![
+            text(match &installation.locale {
+                Some(locale) => format!(
+                    "찾음: {} ({}, {locale})",
+                    installation.arch, installation.source
+                ),
+                None => format!("찾음: {} ({})", installation.arch, installation.source),
+            })
+            .size(12),
+            text(&installation.path).size(10),
+        ]
+        .width(Length::Fill),
+        button("추가")
+            .on_press(Message::AddInstallation(index))
+            .padding([6, 12]),
+    ]
+    .spacing(4)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+// 설정 창의 프로필 하나
+fn profile_editor<'a>(
+    index: usize,
+    profile: &'a Profile,
+    args: &str,
+    executable: Option<&Result<PeInfo, String>>,
+) -> Element<'a, Message> {
+    let (status, color) = match executable {
+        Some(Ok(info)) => match arch_mismatch(profile, info) {
+            Some(warning) => (warning, WARNING_COLOR),
+            None => (executable_summary(info), Color::from_rgb(0.4, 0.4, 0.4)),
+        },
+        Some(Err(err)) => (err.clone(), ERROR_COLOR),
+        None => (String::new(), Color::BLACK),
+    };
+
+    column![
+        row![
+            text_input("이름", &profile.name)
+                .on_input(move |name| Message::ProfileNameChanged(index, name))
+                .size(12)
+                .padding([6, 4]),
+            button("삭제")
+                .on_press(Message::RemoveProfile(index))
+                .padding([6, 12]),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center),
+        row![
+            container(text(&profile.path).size(10))
+                .style(|_| container::Style {
+                    border: Border {
+                        color: Color::BLACK,
+                        width: 1.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .width(Length::Fill)
+                .padding([8, 4]),
+            button(
+                svg(iced::widget::svg::Handle::from_memory(SEARCH)).style(|_, _| svg::Style {
+                    color: Some(Color::WHITE)
+                })
+            )
+            .width(36)
+            .height(36)
+            .padding(4)
+            .on_press(Message::OpenFolderDialog(index)),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center),
+        text(status).size(10).color(color),
+        text_input("인자", args)
+            .on_input(move |args| Message::ProfileArgsChanged(index, args))
+            .size(12)
+            .padding([6, 4]),
+        checkbox(
+            "Battle.net으로 실행",
+            profile.launcher == Launcher::BattleNet
+        )
+        .on_toggle(move |battle_net| Message::ProfileLauncherToggled(index, battle_net))
+        .size(14)
+        .text_size(12),
+    ]
+    .spacing(4)
+    .into()
+}
+
+// 프로필의 아키텍처와 실행 파일의 아키텍처가 다르면 경고 문구
+fn arch_mismatch(profile: &Profile, info: &PeInfo) -> Option<String> {
+    let Some(actual) = info.machine.arch() else {
+        return Some(format!(
+            "경고: 지원하지 않는 실행 파일입니다. ({})",
+            info.machine
+        ));
+    };
+
+    profile
+        .effective_arch()
+        .filter(|expected| *expected != actual)
+        .map(|expected| format!("경고: {expected} 프로필에 {actual} 실행 파일이 설정되었습니다."))
+}
+
+// 예: "1.23.10.13515, AMD64, GUI, 2023-11-14"
+fn executable_summary(info: &PeInfo) -> String {
+    let mut summary = format!("{}, {}", info.machine, info.subsystem);
+    if let Some(version) = info.version() {
+        summary = format!("{version}, {summary}");
+    }
+    if let Some(built_at) = info.built_at() {
+        summary.push_str(&built_at.format(", %Y-%m-%d").to_string());
+    }
+
+    summary
+}
+
 fn modal<'a, Message>(
     base: impl Into<Element<'a, Message>>,
     content: impl Into<Element<'a, Message>>,
@@ -431,3 +1174,512 @@ where
     ]
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use iced::futures::{StreamExt, executor::block_on};
+    use iced_runtime::{Action, task::into_stream};
+
+    use super::*;
+    use scr_multi_launcher::{
+        LaunchRequest, discovery::Source, scr::fake::FakeBackend, settings::Arch,
+    };
+
+    const PATH_32: &str = "C:\\StarCraft\\x86\\StarCraft.exe";
+
+    fn profile() -> Profile {
+        Profile::new("32bit", PATH_32)
+    }
+
+    fn new_app(backend: &FakeBackend) -> App<FakeBackend> {
+        let settings = Settings {
+            profiles: vec![profile()],
+            ..Default::default()
+        };
+
+        App::new(backend.clone(), Some(settings), Ok(None))
+    }
+
+    // Task를 끝까지 실행하고 만든 메시지
+    fn outputs(task: Task<Message>) -> Vec<Message> {
+        let Some(stream) = into_stream(task) else {
+            return Vec::new();
+        };
+
+        block_on(stream.collect::<Vec<_>>())
+            .into_iter()
+            .filter_map(|action| match action {
+                Action::Output(message) => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 메시지를 처리하고 Task가 만든 메시지까지 모두 처리
+    fn drive(app: &mut App<FakeBackend>, message: Message) {
+        let mut queue = VecDeque::from([message]);
+        while let Some(message) = queue.pop_front() {
+            queue.extend(outputs(app.update(message)));
+        }
+    }
+
+    fn has_log(app: &App<FakeBackend>, needle: &str) -> bool {
+        app.logs.iter().any(|log| log.contains(needle))
+    }
+
+    #[test]
+    fn run_scr_spawns_and_closes_mutex() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::RunSCR(profile()));
+
+        assert_eq!(backend.spawned(), [LaunchRequest::new(PATH_32)]);
+        assert!(backend.is_alive(1000));
+        assert!(!backend.has_mutex(1000));
+        assert!(has_log(
+            &app,
+            "Closed HANDLE(0x2c) for StarCraft: Remastered (PID: 1000)"
+        ));
+    }
+
+    #[test]
+    fn run_scr_failure_is_logged() {
+        let backend = FakeBackend::default();
+        backend.fail_spawn(true);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::RunSCR(profile()));
+
+        assert!(backend.spawned().is_empty());
+        assert!(has_log(
+            &app,
+            "[1/1] Failed to launch StarCraft: Remastered"
+        ));
+        assert!(app.batch_cancel.is_none());
+    }
+
+    #[test]
+    fn run_scr_launches_batch_count_clients() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::SetBatchCount(3));
+        drive(&mut app, Message::RunSCR(profile()));
+
+        assert_eq!(backend.spawned().len(), 3);
+        assert!((1000..1003).all(|pid| !backend.has_mutex(pid)));
+        assert!(has_log(&app, "[3/3] Closed HANDLE(0x2c)"));
+        assert!(app.batch_cancel.is_none());
+        // 일괄 실행 단계가 인스턴스 상태에도 반영됨
+        assert!(app.childs.iter().all(|child| {
+            let states: Vec<_> = child.history().iter().map(|t| t.state).collect();
+            states == [State::Launching, State::MutexClosed]
+        }));
+    }
+
+    #[test]
+    fn run_scr_is_ignored_while_batch_runs() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+        app.batch_cancel = Some(Arc::default());
+
+        drive(&mut app, Message::RunSCR(profile()));
+
+        assert!(backend.spawned().is_empty());
+    }
+
+    #[test]
+    fn settings_edit_profiles() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::OpenSettings);
+        drive(&mut app, Message::AddProfile);
+        drive(
+            &mut app,
+            Message::ProfileNameChanged(1, "windowed".to_string()),
+        );
+        drive(
+            &mut app,
+            Message::ProfileArgsChanged(1, "-launch -w ".to_string()),
+        );
+
+        // 입력 중인 공백은 입력 칸에만 남고 빈 인자는 생기지 않음
+        assert_eq!(app.temp_settings.profiles[1].args, ["-launch", "-w"]);
+        assert_eq!(app.args_input, Some((1, "-launch -w ".to_string())));
+
+        drive(
+            &mut app,
+            Message::ProfileArgsChanged(
+                1,
+                r#"-launch  "two words" --exec="launch S1" """#.to_string(),
+            ),
+        );
+        drive(&mut app, Message::RemoveProfile(0));
+
+        let profiles = &app.temp_settings.profiles;
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "windowed");
+        assert_eq!(
+            profiles[0].args,
+            ["-launch", "two words", "--exec=launch S1"]
+        );
+        assert_eq!(app.args_input, None);
+        assert_eq!(
+            join_args(&profiles[0].args),
+            r#"-launch "two words" --exec="launch S1""#
+        );
+        assert_eq!(app.settings.profiles, [profile()]);
+    }
+
+    #[test]
+    fn installation_is_added_as_profile() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+        drive(&mut app, Message::OpenSettings);
+        drive(
+            &mut app,
+            Message::InstallationsFound(vec![Installation {
+                path: "D:\\StarCraft\\x86\\StarCraft.exe".to_string(),
+                arch: Arch::X86,
+                source: Source::Registry,
+                locale: None,
+            }]),
+        );
+
+        drive(&mut app, Message::AddInstallation(0));
+
+        let added = &app.temp_settings.profiles[1];
+        assert_eq!(added.name, "32bit 2");
+        assert_eq!(added.path, "D:\\StarCraft\\x86\\StarCraft.exe");
+        assert_eq!(added.arch, Some(Arch::X86));
+        assert_eq!(app.installation_rows().count(), 0);
+    }
+
+    #[test]
+    fn non_pe_executable_is_rejected() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+        let path = std::env::temp_dir().join(format!("scr-not-pe-{}.exe", std::process::id()));
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+
+        drive(&mut app, Message::OpenSettings);
+        let selected = outputs(app.select_executable(0, path.display().to_string()));
+        assert_eq!(app.settings_error, None);
+        for message in selected {
+            drive(&mut app, message);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(app.temp_settings.profiles[0].path, PATH_32);
+        assert!(app.settings_error.as_ref().unwrap().contains("MZ"));
+    }
+
+    // 지문을 저장한 뒤 내용을 바꾼 실행 파일과 그 경로의 프로필
+    fn updated_executable(name: &str, block_on_update: bool) -> (Settings, Profile) {
+        let path = std::env::temp_dir().join(format!("scr-{name}-{}.exe", std::process::id()));
+        std::fs::write(&path, "before").unwrap();
+        let fingerprint = Fingerprint::of(&path).unwrap();
+        std::fs::write(&path, "patched").unwrap();
+        let profile = Profile::new("patched", path.display().to_string());
+        let settings = Settings {
+            block_on_update,
+            profiles: vec![profile.clone()],
+            fingerprints: BTreeMap::from([(profile.path.clone(), fingerprint)]),
+            ..Default::default()
+        };
+
+        (settings, profile)
+    }
+
+    #[test]
+    fn updated_executable_is_recorded() {
+        let backend = FakeBackend::default();
+        let (settings, profile) = updated_executable("updated", false);
+        let mut app = App::new(backend.clone(), Some(settings), Ok(None));
+
+        // 지문은 Task에서 확인하고, 저장 Task는 실행하지 않음
+        let checked = outputs(app.update(Message::CheckExecutables));
+        assert!(!has_log(&app, "was updated"));
+        for message in checked {
+            let _ = app.update(message);
+        }
+        let current = Fingerprint::of(&profile.path).unwrap();
+        std::fs::remove_file(&profile.path).unwrap();
+
+        assert!(has_log(&app, "StarCraft: Remastered was updated"));
+        assert_eq!(app.settings.fingerprints[&profile.path], current);
+        assert!(app.pending_updates.is_empty());
+    }
+
+    #[test]
+    fn updated_executable_blocks_until_acknowledged() {
+        let backend = FakeBackend::default();
+        let (settings, profile) = updated_executable("blocked", true);
+        let mut app = App::new(backend.clone(), Some(settings), Ok(None));
+
+        drive(&mut app, Message::CheckExecutables);
+        drive(&mut app, Message::RunSCR(profile.clone()));
+        assert!(has_log(&app, "StarCraft: Remastered was updated"));
+        assert!(has_log(&app, "blocked until the update is acknowledged"));
+        assert!(backend.spawned().is_empty());
+
+        let _ = app.update(Message::AcknowledgeUpdates);
+        drive(&mut app, Message::RunSCR(profile.clone()));
+        std::fs::remove_file(&profile.path).unwrap();
+
+        assert!(app.pending_updates.is_empty());
+        assert_eq!(backend.spawned().len(), 1);
+    }
+
+    #[test]
+    fn run_scr_launches_after_fingerprint_task() {
+        let backend = FakeBackend::default();
+        let path = std::env::temp_dir().join(format!("scr-deferred-{}.exe", std::process::id()));
+        std::fs::write(&path, "current").unwrap();
+        let profile = Profile::new("deferred", path.display().to_string());
+        let settings = Settings {
+            profiles: vec![profile.clone()],
+            fingerprints: BTreeMap::from([(profile.path.clone(), Fingerprint::of(&path).unwrap())]),
+            ..Default::default()
+        };
+        let mut app = App::new(backend.clone(), Some(settings), Ok(None));
+
+        // 지문은 Task에서 확인하므로 메시지를 처리한 직후에는 아직 실행하지 않음
+        let _ = app.update(Message::RunSCR(profile.clone()));
+        assert!(backend.spawned().is_empty());
+
+        drive(&mut app, Message::RunSCR(profile));
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(backend.spawned().len(), 1);
+    }
+
+    #[test]
+    fn arch_mismatch_is_reported() {
+        let info = |machine| PeInfo {
+            machine,
+            subsystem: pe::Subsystem::Gui,
+            timestamp: 0,
+            file_version: None,
+            product_version: None,
+        };
+        let x64 = Profile::new("64bit", "C:\\StarCraft\\x86_64\\StarCraft.exe");
+        let unknown = Profile::new("custom", "D:\\StarCraft.exe");
+
+        assert_eq!(
+            arch_mismatch(&x64, &info(pe::Machine::I386)).unwrap(),
+            "경고: x86_64 프로필에 x86 실행 파일이 설정되었습니다."
+        );
+        assert_eq!(arch_mismatch(&x64, &info(pe::Machine::Amd64)), None);
+        assert_eq!(arch_mismatch(&unknown, &info(pe::Machine::I386)), None);
+        assert!(arch_mismatch(&unknown, &info(pe::Machine::Other(0xaa64))).is_some());
+    }
+
+    #[test]
+    fn tick_processes_new_client_once() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        drive(&mut app, Message::Tick);
+
+        assert!(!backend.has_mutex(42));
+        assert_eq!(app.childs.len(), 1);
+        assert!(
+            app.childs
+                .iter()
+                .all(|child| child.state() == Some(State::MutexClosed))
+        );
+        // 찾음, 뮤텍스 닫음 두 번의 상태 변화만 기록
+        assert_eq!(app.logs.len(), 2);
+        assert!(has_log(&app, "Found StarCraft: Remastered (PID: 42)"));
+    }
+
+    #[test]
+    fn tick_is_skipped_while_previous_check_runs() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        let mut app = new_app(&backend);
+
+        let first = outputs(app.update(Message::Tick));
+        // 앞의 확인 결과가 오기 전의 Tick은 같은 클라이언트를 다시 확인하지 않음
+        assert!(outputs(app.update(Message::Tick)).is_empty());
+        for message in first {
+            drive(&mut app, message);
+        }
+        assert_eq!(app.logs.len(), 2);
+        assert_eq!(app.processes.lock().unwrap().len(), 1);
+
+        backend.exit_with(42, 0);
+        drive(&mut app, Message::Tick);
+
+        assert!(app.childs.is_empty());
+        assert!(app.processes.lock().unwrap().is_empty());
+        assert!(has_log(&app, "StarCraft: Remastered (PID: 42) exited"));
+    }
+
+    #[test]
+    fn tick_reports_crashed_client() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        backend.add_process(43);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        backend.exit_with(42, 0xc0000005);
+        backend.exit_with(43, 0);
+        drive(&mut app, Message::Tick);
+        drive(&mut app, Message::Tick);
+
+        assert!(app.childs.is_empty());
+        assert!(has_log(
+            &app,
+            "StarCraft: Remastered (PID: 42) crashed with exit code 0xc0000005"
+        ));
+        assert!(has_log(&app, "StarCraft: Remastered (PID: 43) exited"));
+        assert_eq!(app.logs.len(), 6);
+    }
+
+    #[test]
+    fn battle_net_client_is_picked_up() {
+        let backend = FakeBackend::default();
+        let mut app = new_app(&backend);
+        let profile = Profile {
+            launcher: Launcher::BattleNet,
+            ..profile()
+        };
+
+        drive(&mut app, Message::RunSCR(profile));
+        // Battle.net(PID 1000)이 실행한 클라이언트
+        backend.add_child(2000, 1000);
+        drive(&mut app, Message::Tick);
+
+        assert_eq!(backend.spawned()[0].path, battle_net::DEFAULT_PATH);
+        assert_eq!(backend.spawned()[0].args, [battle_net::LAUNCH_ARG]);
+        assert!(!backend.has_mutex(2000));
+        assert!(has_log(
+            &app,
+            "Battle.net (PID: 1000) launched StarCraft.exe (PID: 2000) for 32bit"
+        ));
+        assert!(app.pending_launches.is_empty());
+    }
+
+    #[test]
+    fn tick_drops_exited_client() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        backend.exit(42);
+        drive(&mut app, Message::Tick);
+
+        assert!(app.childs.is_empty());
+        assert!(has_log(&app, "Invalid PID: 42"));
+    }
+
+    #[test]
+    fn reused_pid_is_tracked_as_new_client() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        let before = app.childs.first().unwrap().id();
+        backend.reuse_pid(42);
+        drive(&mut app, Message::Tick);
+
+        let after = app.childs.first().unwrap();
+        assert!(has_log(&app, "Invalid PID: 42"));
+        assert_eq!(app.childs.len(), 1);
+        assert_ne!(after.id(), before);
+        assert_eq!(after.state(), Some(State::MutexClosed));
+        assert!(!backend.has_mutex(42));
+    }
+
+    #[test]
+    fn tick_cycle_leaves_no_open_handles() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        backend.add_process(43);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        // 실행 중인 클라이언트마다 핸들 하나만 유지
+        assert_eq!(backend.open_handles(), 2);
+        backend.exit_with(42, 0xc0000005);
+        backend.reuse_pid(43);
+        drive(&mut app, Message::Tick);
+        assert_eq!(backend.open_handles(), 1);
+        drive(&mut app, Message::KillAll);
+        drive(&mut app, Message::Tick);
+
+        assert!(app.childs.is_empty());
+        assert_eq!(backend.open_handles(), 0);
+    }
+
+    #[test]
+    fn game_definition_selects_clients_and_locks() {
+        let backend = FakeBackend::default();
+        let game = GameDefinition {
+            display_name: "StarCraft (test build)".to_string(),
+            image_names: vec!["StarCraft_test.exe".to_string()],
+            lock_names: vec!["Other Lock".to_string()],
+            default_args: vec!["-test".to_string()],
+            ..Default::default()
+        };
+        let mut app = App::new(backend.clone(), Some(Settings::default()), Ok(Some(game)));
+        // StarCraft.exe는 이 게임의 클라이언트가 아님
+        backend.add_process(42);
+        let (pid, _) = backend
+            .spawn(&LaunchRequest::new("C:\\StarCraft\\StarCraft_test.exe"))
+            .unwrap();
+
+        drive(&mut app, Message::Tick);
+        let _ = app.update(Message::AddProfile);
+
+        assert_eq!(app.title(), "SC:R Multi-Launcher - StarCraft (test build)");
+        assert_eq!(app.childs.len(), 1);
+        assert_eq!(app.childs.first().unwrap().pid, pid);
+        // 뮤텍스 이름이 달라 닫지 않음
+        assert!(backend.has_mutex(pid));
+        assert!(backend.has_mutex(42));
+        assert_eq!(app.temp_settings.profiles[0].args, ["-test"]);
+    }
+
+    #[test]
+    fn kill_all_spares_reused_pid() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        backend.reuse_pid(42);
+        let _ = app.update(Message::KillAll);
+
+        assert!(backend.is_alive(42));
+        assert!(!has_log(&app, "terminated process"));
+    }
+
+    #[test]
+    fn kill_all_terminates_every_client() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        backend.add_process(43);
+        let mut app = new_app(&backend);
+
+        drive(&mut app, Message::Tick);
+        drive(&mut app, Message::KillAll);
+
+        assert!(!backend.is_alive(42));
+        assert!(!backend.is_alive(43));
+        assert!(app.childs.is_empty());
+        assert!(has_log(&app, "terminated process wid PID 42"));
+        assert!(has_log(&app, "terminated process wid PID 43"));
+    }
+}
@@ -0,0 +1,371 @@
+//! 여러 클라이언트를 차례로 실행
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    Event,
+    game::GameDefinition,
+    pe::{self, Version},
+    scr::{MUTEX_TIMEOUT, ProcessBackend, wait_mutex_until},
+    settings::Profile,
+};
+
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 같은 프로필로 클라이언트 여러 개를 실행
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchLaunch {
+    /// 실행할 프로필, 인자는 클라이언트마다 `slot`을 바꿔 치환
+    pub profile: Profile,
+    /// 실행할 클라이언트 수
+    pub count: u32,
+    /// 뮤텍스를 닫은 뒤 다음 클라이언트를 실행하기까지 대기 시간
+    pub delay: Duration,
+    /// 클라이언트마다 뮤텍스가 닫히기를 기다리는 최대 시간
+    pub timeout: Duration,
+}
+
+impl BatchLaunch {
+    /// 대기 없이 `count`개를 실행
+    pub fn new(profile: Profile, count: u32) -> Self {
+        Self {
+            profile,
+            count,
+            delay: Duration::ZERO,
+            timeout: MUTEX_TIMEOUT,
+        }
+    }
+}
+
+/// 일괄 실행 중 한 클라이언트의 진행 상황
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// 0부터 시작하는 순서
+    pub index: u32,
+    /// 전체 클라이언트 수
+    pub count: u32,
+    /// 진행 단계
+    pub step: BatchStep,
+}
+
+/// [`BatchProgress`]의 단계
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchStep {
+    /// 클라이언트를 실행함
+    Launched {
+        /// 프로세스 ID
+        pid: u32,
+        /// 프로세스 생성 시각, 모르면 `None`
+        created: Option<u64>,
+        /// 실행 파일 버전, 모르면 `None`
+        version: Option<Version>,
+    },
+    /// 뮤텍스를 닫아 다음 클라이언트를 실행할 수 있음
+    Ready(Event),
+    /// 뮤텍스를 닫지 못해 일괄 실행을 멈춤
+    NotReady {
+        /// 프로세스 ID
+        pid: u32,
+    },
+    /// 실행에 실패해 일괄 실행을 멈춤
+    Failed,
+    /// 취소되어 이후 클라이언트를 실행하지 않음
+    Cancelled,
+}
+
+impl BatchProgress {
+    /// `[1/3] ...` 모양의 로그 문구, 클라이언트는 `game`의 이름으로 부름
+    pub fn describe(&self, game: &GameDefinition) -> String {
+        let name = &game.display_name;
+        let step = match &self.step {
+            BatchStep::Launched { pid, version, .. } => Event::Launched {
+                pid: *pid,
+                version: *version,
+            }
+            .describe(game),
+            BatchStep::Ready(event) => event.describe(game),
+            BatchStep::NotReady { pid } => format!("Mutex was not closed for {name} (PID: {pid})"),
+            BatchStep::Failed => format!("Failed to launch {name}"),
+            BatchStep::Cancelled => "Cancelled".to_string(),
+        };
+
+        format!("[{}/{}] {step}", self.index + 1, self.count)
+    }
+}
+
+/// 클라이언트를 하나씩 실행하고 뮤텍스가 닫히면 다음 클라이언트를 실행
+///
+/// `cancel`이 설정되면 남은 클라이언트는 실행하지 않는다. 실행한 클라이언트 수를 반환한다.
+pub fn run_batch<B: ProcessBackend>(
+    backend: &B,
+    batch: &BatchLaunch,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(BatchProgress),
+) -> u32 {
+    let mut report = |index, step| {
+        on_progress(BatchProgress {
+            index,
+            count: batch.count,
+            step,
+        })
+    };
+    let mut launched = 0;
+    // 실행 중에 파일이 바뀌는 일은 드무니 한 번만 읽음
+    let version = pe::inspect(&batch.profile.path)
+        .ok()
+        .and_then(|info| info.version());
+
+    for index in 0..batch.count {
+        if index > 0 {
+            sleep(batch.delay, cancel);
+        }
+        if cancel.load(Ordering::Relaxed) {
+            report(index, BatchStep::Cancelled);
+            break;
+        }
+
+        let Some((pid, handle)) = backend.spawn(&batch.profile.request(index + 1)) else {
+            report(index, BatchStep::Failed);
+            break;
+        };
+        launched += 1;
+        let created = backend.creation_time(&handle);
+        report(
+            index,
+            BatchStep::Launched {
+                pid,
+                created,
+                version,
+            },
+        );
+
+        if backend.closes_mutex() {
+            match wait_mutex_until(backend, pid, handle, batch.timeout, cancel) {
+                Some(event) => report(index, BatchStep::Ready(event)),
+                None if cancel.load(Ordering::Relaxed) => {}
+                None => {
+                    report(index, BatchStep::NotReady { pid });
+                    break;
+                }
+            }
+        }
+    }
+
+    launched
+}
+
+// 취소되면 바로 깨어남
+fn sleep(duration: Duration, cancel: &AtomicBool) {
+    let mut slept = Duration::ZERO;
+    while slept < duration && !cancel.load(Ordering::Relaxed) {
+        let step = CANCEL_POLL_INTERVAL.min(duration - slept);
+        thread::sleep(step);
+        slept += step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Mutex};
+
+    use super::*;
+    use crate::{
+        SCRStruct,
+        scr::{fake::FakeBackend, poll},
+    };
+
+    fn batch(count: u32) -> BatchLaunch {
+        BatchLaunch::new(Profile::new("test", "StarCraft.exe"), count)
+    }
+
+    fn steps(progress: &[BatchProgress]) -> Vec<(u32, BatchStep)> {
+        progress.iter().map(|p| (p.index, p.step.clone())).collect()
+    }
+
+    #[test]
+    fn launches_each_client_after_previous_mutex_closed() {
+        let backend = FakeBackend::default();
+        let mut progress = Vec::new();
+
+        let launched = run_batch(&backend, &batch(3), &AtomicBool::new(false), |p| {
+            progress.push(p)
+        });
+
+        assert_eq!(launched, 3);
+        assert_eq!(
+            steps(&progress),
+            [1000, 1001, 1002]
+                .into_iter()
+                .zip(0..)
+                .flat_map(|(pid, index)| [
+                    (
+                        index,
+                        BatchStep::Launched {
+                            pid,
+                            created: Some(u64::from(index) + 1),
+                            version: None
+                        }
+                    ),
+                    (
+                        index,
+                        BatchStep::Ready(Event::MutexClosed { pid, handle: 0x2c })
+                    ),
+                ])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            progress[1].describe(&GameDefinition::default()),
+            "[1/3] Closed HANDLE(0x2c) for StarCraft: Remastered (PID: 1000)"
+        );
+        assert_eq!(backend.open_handles(), 0);
+    }
+
+    #[test]
+    fn launch_line_includes_version() {
+        let progress = BatchProgress {
+            index: 0,
+            count: 2,
+            step: BatchStep::Launched {
+                pid: 1000,
+                created: None,
+                version: Some(Version {
+                    major: 1,
+                    minor: 23,
+                    patch: 10,
+                    build: 13515,
+                }),
+            },
+        };
+
+        let test_build = GameDefinition {
+            display_name: "StarCraft (test build)".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            progress.describe(&GameDefinition::default()),
+            "[1/2] Launched StarCraft: Remastered 1.23.10.13515 (PID: 1000)"
+        );
+        assert_eq!(
+            progress.describe(&test_build),
+            "[1/2] Launched StarCraft (test build) 1.23.10.13515 (PID: 1000)"
+        );
+    }
+
+    #[test]
+    fn each_client_gets_own_slot() {
+        let backend = FakeBackend::default();
+        let mut batch = batch(2);
+        batch.profile.args = vec!["-slot={slot}".to_string()];
+
+        run_batch(&backend, &batch, &AtomicBool::new(false), |_| {});
+
+        let args: Vec<_> = backend
+            .spawned()
+            .into_iter()
+            .map(|request| request.args)
+            .collect();
+        assert_eq!(args, [["-slot=1"], ["-slot=2"]]);
+    }
+
+    #[test]
+    fn cancel_stops_remaining_clients() {
+        let backend = FakeBackend::default();
+        let cancel = AtomicBool::new(false);
+        let mut progress = Vec::new();
+
+        let launched = run_batch(&backend, &batch(4), &cancel, |p| {
+            if matches!(p.step, BatchStep::Ready(_)) {
+                cancel.store(true, Ordering::Relaxed);
+            }
+            progress.push(p);
+        });
+
+        assert_eq!(launched, 1);
+        assert_eq!(backend.spawned().len(), 1);
+        assert_eq!(progress.last().unwrap().step, BatchStep::Cancelled);
+        assert_eq!(progress.last().unwrap().index, 1);
+    }
+
+    #[test]
+    fn stops_when_mutex_is_not_closed() {
+        let backend = FakeBackend::default();
+        backend.spawn_without_mutex(true);
+        let mut progress = Vec::new();
+
+        let launched = run_batch(
+            &backend,
+            &BatchLaunch {
+                timeout: Duration::ZERO,
+                ..batch(3)
+            },
+            &AtomicBool::new(false),
+            |p| progress.push(p),
+        );
+
+        assert_eq!(launched, 1);
+        assert_eq!(
+            progress.last().unwrap().step,
+            BatchStep::NotReady { pid: 1000 }
+        );
+        assert_eq!(backend.open_handles(), 0);
+    }
+
+    #[test]
+    fn mutex_closed_by_tick_counts_as_ready() {
+        let backend = FakeBackend::default();
+        let tick = backend.clone();
+        let processes = Mutex::new(BTreeMap::new());
+        let mut progress = Vec::new();
+
+        let launched = run_batch(
+            &backend,
+            &BatchLaunch {
+                timeout: Duration::ZERO,
+                ..batch(2)
+            },
+            &AtomicBool::new(false),
+            |p| {
+                // 일괄 실행이 기다리기 전에 Tick이 같은 클라이언트의 뮤텍스를 먼저 닫음
+                if let BatchStep::Launched { pid, created, .. } = p.step {
+                    let child = SCRStruct {
+                        created,
+                        ..SCRStruct::new(pid)
+                    };
+                    let events = poll(&tick, &child, true, &processes);
+                    assert!(events.contains(&Event::MutexClosed { pid, handle: 0x2c }));
+                }
+                progress.push(p);
+            },
+        );
+
+        assert_eq!(launched, 2);
+        assert_eq!(
+            progress
+                .iter()
+                .filter(|p| matches!(p.step, BatchStep::Ready(_)))
+                .count(),
+            2
+        );
+        drop(processes);
+        assert_eq!(backend.open_handles(), 0);
+    }
+
+    #[test]
+    fn stops_when_spawn_fails() {
+        let backend = FakeBackend::default();
+        backend.fail_spawn(true);
+        let mut progress = Vec::new();
+
+        let launched = run_batch(&backend, &batch(3), &AtomicBool::new(false), |p| {
+            progress.push(p)
+        });
+
+        assert_eq!(launched, 0);
+        assert_eq!(steps(&progress), [(0, BatchStep::Failed)]);
+    }
+}
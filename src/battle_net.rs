@@ -0,0 +1,205 @@
+//! Battle.net을 거친 실행
+//!
+//! 일부 계정은 Battle.net 인증을 거쳐야 하므로 `Battle.net.exe --exec="launch S1"`으로 실행한다.
+//! 이때 StarCraft.exe는 Battle.net이 만들기 때문에, 실행한 뒤 새로 나타난 StarCraft.exe를 부모
+//! 프로세스와 실행 시각으로 실행 요청과 짝짓는다.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::{ProcessBackend, SCRStruct};
+
+/// Battle.net 기본 설치 경로
+pub const DEFAULT_PATH: &str = "C:\\Program Files (x86)\\Battle.net\\Battle.net.exe";
+
+/// Battle.net에 StarCraft: Remastered 실행을 맡기는 인자
+///
+/// Windows 명령줄에는 값만 따옴표로 감싼 `--exec="launch S1"`로 들어간다.
+pub const LAUNCH_ARG: &str = "--exec=launch S1";
+
+/// Battle.net이 StarCraft.exe를 실행하기를 기다리는 최대 시간
+pub const LAUNCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+const IMAGE_NAME: &str = "Battle.net.exe";
+
+/// StarCraft.exe가 나타나기를 기다리는 Battle.net 실행
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingLaunch {
+    /// 프로필 이름
+    pub profile: String,
+    /// 실행한 Battle.net.exe의 PID
+    pub launcher_pid: u32,
+    /// 실행한 시각
+    pub started: Instant,
+}
+
+impl PendingLaunch {
+    /// 지금 실행한 `launcher_pid`를 기다림
+    pub fn new(profile: impl Into<String>, launcher_pid: u32) -> Self {
+        Self {
+            profile: profile.into(),
+            launcher_pid,
+            started: Instant::now(),
+        }
+    }
+}
+
+/// [`correlate`]가 정리한 실행, `Display`는 로그 문구
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Handoff {
+    /// Battle.net이 StarCraft.exe를 실행함
+    Matched {
+        /// 짝지은 실행
+        launch: PendingLaunch,
+        /// 새로 나타난 StarCraft.exe의 PID
+        pid: u32,
+    },
+    /// [`LAUNCH_TIMEOUT`] 안에 StarCraft.exe가 나타나지 않음
+    Expired(PendingLaunch),
+}
+
+impl fmt::Display for Handoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Handoff::Matched { launch, pid } => write!(
+                f,
+                "Battle.net (PID: {}) launched StarCraft.exe (PID: {pid}) for {}",
+                launch.launcher_pid, launch.profile
+            ),
+            Handoff::Expired(launch) => write!(
+                f,
+                "Battle.net (PID: {}) did not launch StarCraft.exe for {} within {}s",
+                launch.launcher_pid,
+                launch.profile,
+                LAUNCH_TIMEOUT.as_secs()
+            ),
+        }
+    }
+}
+
+/// 새로 나타난 StarCraft.exe `appeared`를 기다리는 실행 `pending`과 짝지음
+///
+/// Battle.net이 이미 실행 중이면 새 Battle.net.exe는 명령만 넘기고 종료하므로, 부모가 실행한
+/// 프로세스가 아니어도 이름이 Battle.net.exe이면 가장 오래 기다린 실행과 짝짓는다. 부모를 모르면
+/// 시각만 본다. 짝지은 실행과 `now`에 [`LAUNCH_TIMEOUT`]이 지난 실행은 `pending`에서 빠진다.
+pub fn correlate<B: ProcessBackend>(
+    backend: &B,
+    pending: &mut Vec<PendingLaunch>,
+    appeared: &[SCRStruct],
+    now: Instant,
+) -> Vec<Handoff> {
+    let mut handoffs = Vec::new();
+    pending.retain(|launch| {
+        let expired = now.saturating_duration_since(launch.started) > LAUNCH_TIMEOUT;
+        if expired {
+            handoffs.push(Handoff::Expired(launch.clone()));
+        }
+
+        !expired
+    });
+
+    for child in appeared {
+        if pending.is_empty() {
+            break;
+        }
+        let index = match child.parent_pid {
+            Some(parent) => match pending.iter().position(|l| l.launcher_pid == parent) {
+                Some(index) => index,
+                None if is_battle_net(backend, parent) => 0,
+                // Battle.net과 상관없이 실행된 클라이언트
+                None => continue,
+            },
+            None => 0,
+        };
+        handoffs.push(Handoff::Matched {
+            launch: pending.remove(index),
+            pid: child.pid,
+        });
+    }
+
+    handoffs
+}
+
+fn is_battle_net<B: ProcessBackend>(backend: &B, pid: u32) -> bool {
+    backend
+        .process_name(pid)
+        .is_some_and(|name| name.eq_ignore_ascii_case(IMAGE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LaunchRequest, scr::fake::FakeBackend};
+
+    fn launch_battle_net(backend: &FakeBackend) -> u32 {
+        let (pid, _) = backend.spawn(&LaunchRequest::new(DEFAULT_PATH)).unwrap();
+        pid
+    }
+
+    #[test]
+    fn matches_children_of_battle_net() {
+        let backend = FakeBackend::default();
+        let running = launch_battle_net(&backend);
+        let first = PendingLaunch::new("first", launch_battle_net(&backend));
+        let second = PendingLaunch::new("second", launch_battle_net(&backend));
+        let mut pending = vec![first.clone(), second.clone()];
+        backend.add_process(42);
+
+        let handoffs = correlate(
+            &backend,
+            &mut pending,
+            &[
+                // 두 번째 실행이 직접 만든 클라이언트
+                SCRStruct::with_parent(2000, second.launcher_pid),
+                // 직접 실행한 클라이언트는 건너뜀
+                SCRStruct::with_parent(2001, 42),
+                // 이미 실행 중이던 Battle.net이 만든 클라이언트
+                SCRStruct::with_parent(2002, running),
+            ],
+            Instant::now(),
+        );
+
+        assert_eq!(
+            handoffs,
+            [
+                Handoff::Matched {
+                    launch: second,
+                    pid: 2000
+                },
+                Handoff::Matched {
+                    launch: first,
+                    pid: 2002
+                },
+            ]
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn drops_expired_launches() {
+        let backend = FakeBackend::default();
+        let launch = PendingLaunch::new("late", launch_battle_net(&backend));
+        let mut pending = vec![launch.clone()];
+
+        let waiting = correlate(&backend, &mut pending, &[], launch.started + LAUNCH_TIMEOUT);
+        let expired = correlate(
+            &backend,
+            &mut pending,
+            &[SCRStruct::new(2000)],
+            launch.started + LAUNCH_TIMEOUT + Duration::from_secs(1),
+        );
+
+        assert_eq!(waiting, []);
+        assert_eq!(expired, [Handoff::Expired(launch.clone())]);
+        assert!(pending.is_empty());
+        assert_eq!(
+            expired[0].to_string(),
+            format!(
+                "Battle.net (PID: {}) did not launch StarCraft.exe for late within 60s",
+                launch.launcher_pid
+            )
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use std::{io::Write, sync::atomic::AtomicBool, time::Duration};
+
+use clap::{Parser, Subcommand};
+use serde_json::json;
+
+use scr_multi_launcher::{
+    Event, ProcessBackend, SCRStruct, Settings,
+    batch::{BatchLaunch, run_batch},
+    discovery::Discovery,
+    pe,
+    scr::open_instance,
+    settings::{Isolation, Launcher, WineSettings},
+};
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// StarCraft 클라이언트 실행
+    Launch {
+        /// 실행할 프로필 이름, 생략하면 첫 번째 프로필
+        #[arg(long, short)]
+        profile: Option<String>,
+        /// 실행할 클라이언트 수
+        #[arg(long, default_value_t = 1)]
+        count: u32,
+        /// 클라이언트 사이 대기 시간(ms), 생략하면 conf.toml의 batch_delay
+        #[arg(long)]
+        delay: Option<u64>,
+    },
+    /// 실행 중인 클라이언트 목록
+    List {
+        /// PID, 부모 PID, 생성 시각을 JSON으로 출력
+        ///
+        /// 명령줄은 클라이언트 상태를 추적하지 않으므로 상태는 포함하지 않는다.
+        #[arg(long)]
+        json: bool,
+    },
+    /// 클라이언트 하나를 종료
+    Kill {
+        /// 종료할 클라이언트의 PID
+        #[arg(long)]
+        pid: u32,
+    },
+    /// 모든 클라이언트를 종료
+    KillAll,
+    /// 설정과 실행 중인 클라이언트 상태
+    Status,
+}
+
+pub fn run<B: ProcessBackend>(
+    mut backend: B,
+    maybe_settings: Option<Settings>,
+    command: Command,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let has_settings = maybe_settings.is_some();
+    let settings = maybe_settings
+        .unwrap_or_else(|| Discovery::system(&WineSettings::default()).default_settings());
+    backend.configure(&settings);
+
+    match command {
+        Command::Launch {
+            profile,
+            count,
+            delay,
+        } => {
+            let profile = match &profile {
+                Some(name) => settings
+                    .profile(name)
+                    .ok_or_else(|| format!("{name} 프로필이 없습니다."))?,
+                None => settings
+                    .profiles
+                    .first()
+                    .ok_or("프로필이 설정되지 않았습니다.")?,
+            };
+            // Battle.net이 실행한 클라이언트는 창의 Tick에서 찾음
+            if profile.launcher == Launcher::BattleNet {
+                return Err(format!(
+                    "{} 프로필은 Battle.net으로 실행하므로 창에서만 실행할 수 있습니다.",
+                    profile.name
+                ));
+            }
+            // 복제한 prefix는 클라이언트가 종료될 때 정리하므로 명령줄이 먼저 끝나면 남음
+            if cfg!(not(windows))
+                && profile.isolation.unwrap_or(settings.wine.isolation) == Isolation::Prefix
+            {
+                return Err(format!(
+                    "{} 프로필은 prefix를 분리하여 실행하므로 창에서만 실행할 수 있습니다. \
+                     명령줄에서는 isolation = \"shared\"를 사용하세요.",
+                    profile.name
+                ));
+            }
+            if profile.path.is_empty() {
+                return Err(format!("{} 경로가 설정되지 않았습니다.", profile.name));
+            }
+
+            let batch = BatchLaunch {
+                delay: Duration::from_millis(delay.unwrap_or(settings.batch_delay)),
+                ..BatchLaunch::new(profile.clone(), count)
+            };
+            let mut result = Ok(());
+            let launched = run_batch(&backend, &batch, &AtomicBool::new(false), |progress| {
+                if result.is_ok() {
+                    result = writeln!(out, "{}", progress.describe(backend.game()));
+                }
+            });
+            result.map_err(|e| e.to_string())?;
+            if launched < count {
+                return Err(format!("Launched {launched}/{count} clients"));
+            }
+        }
+        Command::List { json } => {
+            let childs = backend.enumerate();
+            if json {
+                let childs = childs.iter().map(|child| {
+                    json!({
+                        "pid": child.pid,
+                        "parent_pid": child.parent_pid,
+                        "created": child.created,
+                    })
+                });
+                writeln!(out, "{}", json!(childs.collect::<Vec<_>>()))
+            } else {
+                childs
+                    .iter()
+                    .try_for_each(|child| writeln!(out, "{}", child.pid))
+            }
+            .map_err(|e| e.to_string())?;
+        }
+        Command::Kill { pid } => {
+            let childs = backend.enumerate();
+            let Some(child) = childs.iter().find(|child| child.pid == pid) else {
+                return Err(format!(
+                    "PID {pid} is not a {} process",
+                    backend.game().display_name
+                ));
+            };
+            terminate(&backend, child, out)?;
+        }
+        Command::KillAll => {
+            for child in backend.enumerate() {
+                terminate(&backend, &child, out)?;
+            }
+        }
+        Command::Status => {
+            let childs = backend.enumerate();
+            // 버전을 읽을 수 있으면 경로 뒤에 붙임
+            let describe = |path: &str| {
+                if path.is_empty() {
+                    return "(not set)".to_string();
+                }
+                match pe::inspect(path).ok().and_then(|info| info.version()) {
+                    Some(version) => format!("{path} ({version})"),
+                    None => path.to_string(),
+                }
+            };
+
+            writeln!(out, "Game: {}", backend.game().display_name)
+                .and_then(|_| {
+                    writeln!(
+                        out,
+                        "conf.toml: {}",
+                        if has_settings {
+                            "ok"
+                        } else {
+                            "missing or invalid"
+                        },
+                    )
+                })
+                .and_then(|_| {
+                    settings.profiles.iter().try_for_each(|profile| {
+                        writeln!(out, "{}: {}", profile.name, describe(&profile.path))
+                    })
+                })
+                .and_then(|_| writeln!(out, "Running: {}", childs.len()))
+                .and_then(|_| {
+                    childs
+                        .iter()
+                        .try_for_each(|child| writeln!(out, "  PID {}", child.pid))
+                })
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+// 목록을 읽은 뒤 PID가 재사용되었으면 종료하지 않음
+fn terminate<B: ProcessBackend>(
+    backend: &B,
+    child: &SCRStruct,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let pid = child.pid;
+    let handle = open_instance(backend, child).ok_or_else(|| format!("Invalid PID: {pid}"))?;
+    if !backend.terminate(&handle) {
+        return Err(format!("Failed to terminate process with PID {pid}"));
+    }
+
+    writeln!(
+        out,
+        "{}",
+        Event::Terminated { pid }.describe(backend.game())
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scr_multi_launcher::{scr::fake::FakeBackend, settings::Profile};
+
+    fn settings() -> Option<Settings> {
+        let mut windowed = Profile::new("windowed", "C:\\StarCraft\\x86_64\\StarCraft.exe");
+        windowed.args.push("-windowed".to_string());
+
+        Some(Settings {
+            profiles: vec![
+                Profile::new("64bit", "C:\\StarCraft\\x86_64\\StarCraft.exe"),
+                Profile::new("32bit", ""),
+                windowed,
+            ],
+            ..Default::default()
+        })
+    }
+
+    fn run_fake(backend: &FakeBackend, command: Command) -> Result<String, String> {
+        let mut out = Vec::new();
+        run(backend.clone(), settings(), command, &mut out)?;
+
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn launch_spawns_count_clients() {
+        let backend = FakeBackend::default();
+
+        let out = run_fake(
+            &backend,
+            Command::Launch {
+                profile: None,
+                count: 3,
+                delay: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(backend.spawned().len(), 3);
+        assert!(
+            backend
+                .spawned()
+                .iter()
+                .all(|request| request.args == ["-launch"])
+        );
+        assert!((1000..1003).all(|pid| backend.is_alive(pid) && !backend.has_mutex(pid)));
+        assert_eq!(out.matches("Launched StarCraft: Remastered").count(), 3);
+        assert_eq!(out.matches("Closed HANDLE").count(), 3);
+    }
+
+    #[test]
+    fn launch_without_path_fails() {
+        let backend = FakeBackend::default();
+
+        let result = run_fake(
+            &backend,
+            Command::Launch {
+                profile: Some("32bit".to_string()),
+                count: 1,
+                delay: None,
+            },
+        );
+
+        assert!(result.is_err());
+        assert!(backend.spawned().is_empty());
+    }
+
+    #[test]
+    fn launch_selects_profile_by_name() {
+        let backend = FakeBackend::default();
+
+        run_fake(
+            &backend,
+            Command::Launch {
+                profile: Some("windowed".to_string()),
+                count: 1,
+                delay: None,
+            },
+        )
+        .unwrap();
+        let unknown = run_fake(
+            &backend,
+            Command::Launch {
+                profile: Some("missing".to_string()),
+                count: 1,
+                delay: None,
+            },
+        );
+
+        assert_eq!(backend.spawned()[0].args, ["-launch", "-windowed"]);
+        assert!(unknown.is_err());
+        assert_eq!(backend.spawned().len(), 1);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn launch_rejects_prefix_isolation() {
+        let backend = FakeBackend::default();
+        let mut settings = settings().unwrap();
+        settings.wine.isolation = Isolation::Prefix;
+        settings.profiles[2].isolation = Some(Isolation::Shared);
+        let launch = |profile: &str, settings: &Settings| {
+            run(
+                backend.clone(),
+                Some(settings.clone()),
+                Command::Launch {
+                    profile: Some(profile.to_string()),
+                    count: 1,
+                    delay: None,
+                },
+                &mut Vec::new(),
+            )
+        };
+
+        let isolated = launch("64bit", &settings);
+        launch("windowed", &settings).unwrap();
+
+        assert!(isolated.unwrap_err().contains("창에서만"));
+        assert_eq!(backend.spawned().len(), 1);
+        assert_eq!(backend.spawned()[0].isolation, Some(Isolation::Shared));
+    }
+
+    #[test]
+    fn list_json() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        backend.add_child(43, 7);
+
+        let out = run_fake(&backend, Command::List { json: true }).unwrap();
+
+        assert_eq!(
+            out.trim_end(),
+            r#"[{"created":1,"parent_pid":null,"pid":42},{"created":2,"parent_pid":7,"pid":43}]"#
+        );
+    }
+
+    #[test]
+    fn kill_refuses_unknown_pid() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+
+        assert!(run_fake(&backend, Command::Kill { pid: 7 }).is_err());
+        run_fake(&backend, Command::Kill { pid: 42 }).unwrap();
+        assert!(!backend.is_alive(42));
+    }
+
+    #[test]
+    fn kill_all_terminates_every_client() {
+        let backend = FakeBackend::default();
+        backend.add_process(42);
+        backend.add_process(43);
+
+        let out = run_fake(&backend, Command::KillAll).unwrap();
+
+        assert!(!backend.is_alive(42) && !backend.is_alive(43));
+        assert_eq!(out.lines().count(), 2);
+    }
+}
@@ -0,0 +1,686 @@
+//! StarCraft 설치 위치 찾기
+//!
+//! Battle.net의 [`product.db`](crate::product_db), 흔한 설치 폴더, Windows의 Battle.net 제거
+//! 레지스트리, Linux의 wine prefix와 Lutris, Heroic 게임 폴더에서 `x86/StarCraft.exe`와
+//! `x86_64/StarCraft.exe`를 찾는다. 파일 시스템과 레지스트리는 트레이트 뒤에 두어 가짜 트리로
+//! 테스트한다.
+
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    product_db::{self, PRODUCT_DB_PATH},
+    settings::{Arch, Profile, Settings, WineSettings},
+};
+
+#[cfg(windows)]
+mod windows;
+
+#[cfg(windows)]
+pub use windows::WindowsRegistry as SystemRegistry;
+
+/// 현재 플랫폼의 레지스트리, Windows가 아니면 비어 있음
+#[cfg(not(windows))]
+pub use self::NoRegistry as SystemRegistry;
+
+// HKEY_LOCAL_MACHINE 아래의 제거 프로그램 목록, 32비트 앱은 WOW6432Node에 있음
+const UNINSTALL_KEYS: [&str; 2] = [
+    "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+];
+const INSTALL_FOLDER: &str = "StarCraft";
+const EXECUTABLE: &str = "StarCraft.exe";
+const ARCH_FOLDERS: [(Arch, &str); 2] = [(Arch::X86, "x86"), (Arch::X64, "x86_64")];
+// wine prefix 안에서 설치 폴더의 상위 폴더
+const PREFIX_PROGRAM_FILES: [&str; 2] = ["drive_c/Program Files (x86)", "drive_c/Program Files"];
+// 네이티브와 Flatpak 설치의 Heroic 게임별 설정 폴더
+const HEROIC_GAMES_CONFIG: [&str; 2] = [
+    ".config/heroic/GamesConfig",
+    ".var/app/com.heroicgameslauncher.hgl/config/heroic/GamesConfig",
+];
+
+/// 설치 위치를 찾는 데 필요한 파일 시스템 접근
+pub trait FileSystem {
+    /// `path`가 파일인지
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// `path` 디렉터리의 항목 경로, 읽을 수 없으면 빈 목록
+    fn read_dir(&self, path: &Path) -> Vec<PathBuf>;
+
+    /// `path` 파일의 내용, 읽을 수 없으면 `None`
+    fn read(&self, path: &Path) -> Option<Vec<u8>>;
+}
+
+/// 설치 위치를 찾는 데 필요한 `HKEY_LOCAL_MACHINE` 읽기
+pub trait Registry {
+    /// `key`의 하위 키 이름
+    fn subkeys(&self, key: &str) -> Vec<String>;
+
+    /// `key`의 문자열 값 `name`
+    fn value(&self, key: &str, name: &str) -> Option<String>;
+}
+
+/// 실제 파일 시스템
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemFileSystem;
+
+impl FileSystem for SystemFileSystem {
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read_dir(&self, path: &Path) -> Vec<PathBuf> {
+        fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default()
+    }
+
+    fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        fs::read(path).ok()
+    }
+}
+
+/// 레지스트리가 없는 플랫폼에서 쓰는 빈 레지스트리
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRegistry;
+
+impl Registry for NoRegistry {
+    fn subkeys(&self, _key: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn value(&self, _key: &str, _name: &str) -> Option<String> {
+        None
+    }
+}
+
+/// 설치 위치를 찾은 곳
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Battle.net의 product.db
+    ProductDb,
+    /// Program Files 등 흔한 설치 폴더
+    Common,
+    /// Battle.net이 남긴 제거 레지스트리
+    Registry,
+    /// wine prefix
+    Wine,
+    /// Lutris 게임 폴더
+    Lutris,
+    /// Heroic 게임 폴더
+    Heroic,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::ProductDb => write!(f, "product.db"),
+            Source::Common => write!(f, "Program Files"),
+            Source::Registry => write!(f, "Battle.net"),
+            Source::Wine => write!(f, "Wine"),
+            Source::Lutris => write!(f, "Lutris"),
+            Source::Heroic => write!(f, "Heroic"),
+        }
+    }
+}
+
+/// 찾은 StarCraft.exe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installation {
+    /// StarCraft.exe 경로
+    pub path: String,
+    /// 실행 파일이 있는 폴더의 아키텍처
+    pub arch: Arch,
+    /// 찾은 곳
+    pub source: Source,
+    /// 게임 언어, product.db에서 찾았을 때만 있음
+    pub locale: Option<String>,
+}
+
+impl Installation {
+    /// 이 실행 파일을 쓰는 `32bit`/`64bit` 프로필
+    pub fn profile(&self) -> Profile {
+        let name = match self.arch {
+            Arch::X86 => "32bit",
+            Arch::X64 => "64bit",
+        };
+
+        Profile {
+            arch: Some(self.arch),
+            ..Profile::new(name, &self.path)
+        }
+    }
+}
+
+/// 설치 위치 찾기
+#[derive(Debug, Clone)]
+pub struct Discovery<F, R> {
+    /// 파일 시스템
+    pub fs: F,
+    /// 레지스트리
+    pub registry: R,
+    /// product.db가 있는 `ProgramData` 폴더
+    pub program_data: Vec<PathBuf>,
+    /// 흔한 설치 폴더의 상위 폴더 (`Program Files` 등)
+    pub program_files: Vec<PathBuf>,
+    /// 홈 디렉터리, wine/Lutris/Heroic 폴더의 기준
+    pub home: Option<PathBuf>,
+    /// 설정이나 `WINEPREFIX`로 지정한 wine prefix
+    pub wine_prefixes: Vec<PathBuf>,
+}
+
+impl Discovery<SystemFileSystem, SystemRegistry> {
+    /// 실제 파일 시스템과 레지스트리, 환경 변수를 쓰는 설치 위치 찾기
+    pub fn system(wine: &WineSettings) -> Self {
+        let program_files = ["ProgramFiles(x86)", "ProgramFiles"]
+            .into_iter()
+            .filter_map(env::var_os)
+            .map(PathBuf::from)
+            .collect();
+        let wine_prefixes = Some(wine.prefix.clone())
+            .filter(|prefix| !prefix.is_empty())
+            .map(PathBuf::from)
+            .into_iter()
+            .chain(env::var_os("WINEPREFIX").map(PathBuf::from))
+            .collect();
+
+        Self {
+            fs: SystemFileSystem,
+            registry: SystemRegistry,
+            program_data: env::var_os("ProgramData")
+                .map(PathBuf::from)
+                .into_iter()
+                .collect(),
+            program_files,
+            home: env::var_os("HOME").map(PathBuf::from),
+            wine_prefixes,
+        }
+    }
+}
+
+impl<F: FileSystem, R: Registry> Discovery<F, R> {
+    /// 찾은 실행 파일, 같은 경로는 한 번만
+    pub fn find(&self) -> Vec<Installation> {
+        let mut installations: Vec<Installation> = Vec::new();
+
+        for (root, source, locale) in self.install_folders() {
+            for (arch, folder) in ARCH_FOLDERS {
+                let exe = root.join(folder).join(EXECUTABLE);
+                let path = exe.display().to_string();
+                if self.fs.is_file(&exe) && installations.iter().all(|found| found.path != path) {
+                    installations.push(Installation {
+                        path,
+                        arch,
+                        source,
+                        locale: locale.clone(),
+                    });
+                }
+            }
+        }
+
+        installations
+    }
+
+    /// `conf.toml`이 없을 때 쓸 설정, product.db에 기록된 설치마다 프로필을 만듦
+    pub fn default_settings(&self) -> Settings {
+        let profiles = self
+            .find()
+            .into_iter()
+            .filter(|installation| installation.source == Source::ProductDb)
+            .map(|installation| installation.profile())
+            .collect();
+
+        Settings {
+            profiles,
+            ..Default::default()
+        }
+    }
+
+    // StarCraft 설치 폴더 후보와 찾은 곳, product.db에서 찾았으면 언어
+    fn install_folders(&self) -> Vec<(PathBuf, Source, Option<String>)> {
+        let prefixes = self.prefixes();
+
+        let mut folders = self.product_db_folders(&prefixes);
+        folders.extend(
+            self.program_files
+                .iter()
+                .map(|parent| (parent.join(INSTALL_FOLDER), Source::Common, None)),
+        );
+        folders.extend(
+            self.registry_locations()
+                .into_iter()
+                .map(|location| (location, Source::Registry, None)),
+        );
+        for (prefix, source) in prefixes {
+            for program_files in PREFIX_PROGRAM_FILES {
+                folders.push((
+                    prefix.join(program_files).join(INSTALL_FOLDER),
+                    source,
+                    None,
+                ));
+            }
+        }
+
+        folders
+    }
+
+    // 네이티브와 wine prefix 안의 product.db에 기록된 StarCraft 설치 폴더
+    fn product_db_folders(
+        &self,
+        prefixes: &[(PathBuf, Source)],
+    ) -> Vec<(PathBuf, Source, Option<String>)> {
+        let native = self
+            .program_data
+            .iter()
+            .map(|program_data| (program_data.join(PRODUCT_DB_PATH), None));
+        let in_prefixes = prefixes.iter().map(|(prefix, _)| {
+            let drive_c = prefix.join("drive_c");
+            (
+                drive_c.join("ProgramData").join(PRODUCT_DB_PATH),
+                Some(drive_c),
+            )
+        });
+
+        native
+            .chain(in_prefixes)
+            .filter_map(|(db, drive_c)| {
+                let install = product_db::starcraft(&self.fs.read(&db)?).ok()??;
+                let folder = local_folder(&install.install_path, drive_c.as_deref())?;
+                let locale = Some(install.locale).filter(|locale| !locale.is_empty());
+                Some((folder, Source::ProductDb, locale))
+            })
+            .collect()
+    }
+
+    // DisplayName이 StarCraft인 제거 항목의 InstallLocation, StarCraft II는 제외
+    fn registry_locations(&self) -> Vec<PathBuf> {
+        UNINSTALL_KEYS
+            .iter()
+            .flat_map(|parent| {
+                self.registry
+                    .subkeys(parent)
+                    .into_iter()
+                    .map(move |subkey| format!("{parent}\\{subkey}"))
+            })
+            .filter(|key| {
+                self.registry
+                    .value(key, "DisplayName")
+                    .is_some_and(|name| name.trim() == INSTALL_FOLDER)
+            })
+            .filter_map(|key| self.registry.value(&key, "InstallLocation"))
+            .filter(|location| !location.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    // wine prefix 후보, Proton은 prefix가 `pfx` 안에 있음
+    fn prefixes(&self) -> Vec<(PathBuf, Source)> {
+        let mut prefixes: Vec<_> = self
+            .wine_prefixes
+            .iter()
+            .flat_map(|prefix| [prefix.clone(), prefix.join("pfx")])
+            .map(|prefix| (prefix, Source::Wine))
+            .collect();
+        let Some(home) = &self.home else {
+            return prefixes;
+        };
+
+        prefixes.push((home.join(".wine"), Source::Wine));
+
+        // Lutris는 게임마다 ~/Games/<이름>에 prefix를 만들고, 다른 위치는 게임 설정에 적음
+        prefixes.extend(
+            self.fs
+                .read_dir(&home.join("Games"))
+                .into_iter()
+                .map(|prefix| (prefix, Source::Lutris)),
+        );
+        for config in self.fs.read_dir(&home.join(".config/lutris/games")) {
+            if let Some(prefix) = self
+                .fs
+                .read(&config)
+                .and_then(|config| lutris_prefix(&String::from_utf8_lossy(&config)))
+            {
+                prefixes.push((prefix, Source::Lutris));
+            }
+        }
+
+        // Heroic은 기본적으로 ~/Games/Heroic/Prefixes/default/<이름>을 씀
+        prefixes.extend(
+            self.fs
+                .read_dir(&home.join("Games/Heroic/Prefixes/default"))
+                .into_iter()
+                .map(|prefix| (prefix, Source::Heroic)),
+        );
+        for dir in HEROIC_GAMES_CONFIG {
+            for config in self.fs.read_dir(&home.join(dir)) {
+                let prefixes_in_config = self
+                    .fs
+                    .read(&config)
+                    .map(|config| heroic_prefixes(&String::from_utf8_lossy(&config)))
+                    .unwrap_or_default();
+                prefixes.extend(
+                    prefixes_in_config
+                        .into_iter()
+                        .map(|prefix| (prefix, Source::Heroic)),
+                );
+            }
+        }
+
+        prefixes
+    }
+}
+
+// product.db의 `C:/...` 경로를 이 컴퓨터의 경로로, wine prefix 안이면 `drive_c`가 C 드라이브
+fn local_folder(install_path: &str, drive_c: Option<&Path>) -> Option<PathBuf> {
+    let Some(drive_c) = drive_c else {
+        let native = if cfg!(windows) {
+            install_path.replace('/', "\\")
+        } else {
+            install_path.to_string()
+        };
+        return Some(PathBuf::from(native));
+    };
+
+    let rest = install_path
+        .strip_prefix("C:")
+        .or_else(|| install_path.strip_prefix("c:"))?;
+    Some(
+        rest.split(['/', '\\'])
+            .filter(|part| !part.is_empty())
+            .fold(drive_c.to_path_buf(), |path, part| path.join(part)),
+    )
+}
+
+// Lutris 게임 설정(YAML)의 `prefix:` 값
+fn lutris_prefix(config: &str) -> Option<PathBuf> {
+    config.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("prefix:")?.trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        (!value.is_empty()).then(|| PathBuf::from(value))
+    })
+}
+
+// Heroic 게임 설정(JSON)의 게임별 `winePrefix` 값
+fn heroic_prefixes(config: &str) -> Vec<PathBuf> {
+    serde_json::from_str::<BTreeMap<String, serde_json::Value>>(config)
+        .unwrap_or_default()
+        .values()
+        .filter_map(|game| game.get("winePrefix")?.as_str())
+        .filter(|prefix| !prefix.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// 파일 내용만 가진 가짜 트리, 디렉터리는 파일 경로에서 추론
+    #[derive(Default)]
+    struct FakeTree {
+        files: BTreeMap<PathBuf, Vec<u8>>,
+    }
+
+    impl FakeTree {
+        fn with(files: &[(&str, &str)]) -> Self {
+            Self {
+                files: files
+                    .iter()
+                    .map(|(path, contents)| (PathBuf::from(path), contents.as_bytes().to_vec()))
+                    .collect(),
+            }
+        }
+    }
+
+    impl FileSystem for FakeTree {
+        fn is_file(&self, path: &Path) -> bool {
+            self.files.contains_key(path)
+        }
+
+        fn read_dir(&self, path: &Path) -> Vec<PathBuf> {
+            let entries: BTreeSet<_> = self
+                .files
+                .keys()
+                .filter_map(|file| {
+                    let name = file.strip_prefix(path).ok()?.components().next()?;
+                    Some(path.join(name))
+                })
+                .collect();
+            entries.into_iter().collect()
+        }
+
+        fn read(&self, path: &Path) -> Option<Vec<u8>> {
+            self.files.get(path).cloned()
+        }
+    }
+
+    /// 키 경로별 값만 가진 가짜 레지스트리
+    #[derive(Default)]
+    struct FakeRegistry {
+        values: BTreeMap<String, BTreeMap<String, String>>,
+    }
+
+    impl Registry for FakeRegistry {
+        fn subkeys(&self, key: &str) -> Vec<String> {
+            let prefix = format!("{key}\\");
+            self.values
+                .keys()
+                .filter_map(|path| path.strip_prefix(&prefix))
+                .map(str::to_string)
+                .collect()
+        }
+
+        fn value(&self, key: &str, name: &str) -> Option<String> {
+            self.values.get(key)?.get(name).cloned()
+        }
+    }
+
+    fn discovery(fs: FakeTree) -> Discovery<FakeTree, FakeRegistry> {
+        Discovery {
+            fs,
+            registry: FakeRegistry::default(),
+            program_data: Vec::new(),
+            program_files: Vec::new(),
+            home: Some(PathBuf::from("/home/user")),
+            wine_prefixes: Vec::new(),
+        }
+    }
+
+    fn found(discovery: &Discovery<FakeTree, FakeRegistry>) -> Vec<(String, Arch, Source)> {
+        discovery
+            .find()
+            .into_iter()
+            .map(|found| (found.path, found.arch, found.source))
+            .collect()
+    }
+
+    #[test]
+    fn finds_program_files_and_registry() {
+        let mut discovery = discovery(FakeTree::with(&[
+            ("/pf86/StarCraft/x86/StarCraft.exe", ""),
+            ("/pf86/StarCraft/x86_64/StarCraft.exe", ""),
+            ("/games/SC/x86_64/StarCraft.exe", ""),
+            ("/games/SC2/x86_64/StarCraft.exe", ""),
+        ]));
+        discovery.program_files = vec![PathBuf::from("/pf86"), PathBuf::from("/pf")];
+        let key = |name: &str| format!("{}\\{name}", UNINSTALL_KEYS[0]);
+        discovery.registry.values = BTreeMap::from([
+            (
+                key("StarCraft"),
+                BTreeMap::from([
+                    ("DisplayName".to_string(), "StarCraft".to_string()),
+                    ("InstallLocation".to_string(), "/games/SC".to_string()),
+                ]),
+            ),
+            (
+                key("StarCraft II"),
+                BTreeMap::from([
+                    ("DisplayName".to_string(), "StarCraft II".to_string()),
+                    ("InstallLocation".to_string(), "/games/SC2".to_string()),
+                ]),
+            ),
+            // 흔한 설치 폴더와 같은 위치는 한 번만
+            (
+                key("Duplicate"),
+                BTreeMap::from([
+                    ("DisplayName".to_string(), "StarCraft".to_string()),
+                    ("InstallLocation".to_string(), "/pf86/StarCraft".to_string()),
+                ]),
+            ),
+        ]);
+
+        assert_eq!(
+            found(&discovery),
+            [
+                (
+                    "/pf86/StarCraft/x86/StarCraft.exe".to_string(),
+                    Arch::X86,
+                    Source::Common
+                ),
+                (
+                    "/pf86/StarCraft/x86_64/StarCraft.exe".to_string(),
+                    Arch::X64,
+                    Source::Common
+                ),
+                (
+                    "/games/SC/x86_64/StarCraft.exe".to_string(),
+                    Arch::X64,
+                    Source::Registry
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_wine_lutris_and_heroic_prefixes() {
+        let mut discovery = discovery(FakeTree::with(&[
+            (
+                "/proton/pfx/drive_c/Program Files (x86)/StarCraft/x86_64/StarCraft.exe",
+                "",
+            ),
+            (
+                "/home/user/.wine/drive_c/Program Files/StarCraft/x86/StarCraft.exe",
+                "",
+            ),
+            (
+                "/home/user/Games/battlenet/drive_c/Program Files (x86)/StarCraft/x86_64/StarCraft.exe",
+                "",
+            ),
+            (
+                "/home/user/.config/lutris/games/starcraft.yml",
+                "game:\n  exe: Battle.net.exe\n  prefix: '/data/lutris'\n",
+            ),
+            (
+                "/data/lutris/drive_c/Program Files (x86)/StarCraft/x86/StarCraft.exe",
+                "",
+            ),
+            (
+                "/home/user/.config/heroic/GamesConfig/bnet.json",
+                r#"{"bnet": {"winePrefix": "/data/heroic", "wineVersion": {}}}"#,
+            ),
+            (
+                "/data/heroic/drive_c/Program Files (x86)/StarCraft/x86_64/StarCraft.exe",
+                "",
+            ),
+            ("/home/user/.config/heroic/GamesConfig/broken.json", "{"),
+        ]));
+        discovery.wine_prefixes = vec![PathBuf::from("/proton")];
+
+        let expected = [
+            ("/proton/pfx/", Arch::X64, Source::Wine),
+            ("/home/user/.wine/", Arch::X86, Source::Wine),
+            ("/home/user/Games/battlenet/", Arch::X64, Source::Lutris),
+            ("/data/lutris/", Arch::X86, Source::Lutris),
+            ("/data/heroic/", Arch::X64, Source::Heroic),
+        ];
+
+        let found = found(&discovery);
+        assert_eq!(found.len(), expected.len(), "{found:?}");
+        for ((path, arch, source), (prefix, expected_arch, expected_source)) in
+            found.into_iter().zip(expected)
+        {
+            assert!(path.starts_with(prefix), "{path}");
+            assert_eq!((arch, source), (expected_arch, expected_source), "{path}");
+        }
+    }
+
+    #[test]
+    fn product_db_gives_default_profiles() {
+        let mut tree = FakeTree::with(&[
+            (
+                "/home/user/.wine/drive_c/Program Files (x86)/StarCraft/x86/StarCraft.exe",
+                "",
+            ),
+            (
+                "/home/user/.wine/drive_c/Program Files (x86)/StarCraft/x86_64/StarCraft.exe",
+                "",
+            ),
+            (
+                "/home/user/Games/bnet/drive_c/ProgramData/Battle.net/Agent/product.db",
+                "",
+            ),
+        ]);
+        tree.files.insert(
+            PathBuf::from("/home/user/.wine/drive_c/ProgramData/Battle.net/Agent/product.db"),
+            include_bytes!("../testdata/product.db").to_vec(),
+        );
+        let discovery = discovery(tree);
+
+        let found = discovery.find();
+        let settings = discovery.default_settings();
+
+        assert!(
+            found.iter().all(|found| found.source == Source::ProductDb
+                && found.locale.as_deref() == Some("koKR"))
+        );
+        let profiles: Vec<_> = settings
+            .profiles
+            .iter()
+            .map(|profile| (profile.name.as_str(), profile.arch))
+            .collect();
+        assert_eq!(
+            profiles,
+            [("32bit", Some(Arch::X86)), ("64bit", Some(Arch::X64))]
+        );
+        assert_eq!(
+            settings.profiles[1].path,
+            "/home/user/.wine/drive_c/Program Files (x86)/StarCraft/x86_64/StarCraft.exe"
+        );
+    }
+
+    #[test]
+    fn product_db_path_maps_into_prefix() {
+        let folder = local_folder("C:/Program Files (x86)/StarCraft", None).unwrap();
+        let in_prefix =
+            local_folder("C:/Games/StarCraft", Some(Path::new("/prefix/drive_c"))).unwrap();
+
+        assert_eq!(folder, Path::new("C:/Program Files (x86)/StarCraft"));
+        assert_eq!(in_prefix, Path::new("/prefix/drive_c/Games/StarCraft"));
+        assert_eq!(
+            local_folder("D:/StarCraft", Some(Path::new("/prefix/drive_c"))),
+            None
+        );
+    }
+
+    #[test]
+    fn installation_becomes_profile() {
+        let installation = Installation {
+            path: "C:\\StarCraft\\x86\\StarCraft.exe".to_string(),
+            arch: Arch::X86,
+            source: Source::Registry,
+            locale: None,
+        };
+
+        let profile = installation.profile();
+
+        assert_eq!(profile.name, "32bit");
+        assert_eq!(profile.arch, Some(Arch::X86));
+        assert_eq!(profile.path, installation.path);
+        assert_eq!(profile.args, ["-launch"]);
+    }
+}
@@ -0,0 +1,97 @@
+use windows::{
+    Win32::System::Registry::{
+        HKEY, HKEY_LOCAL_MACHINE, KEY_READ, RRF_RT_REG_SZ, RegCloseKey, RegEnumKeyExW,
+        RegGetValueW, RegOpenKeyExW,
+    },
+    core::{HSTRING, PWSTR},
+};
+
+use super::Registry;
+
+// 레지스트리 키 이름의 최대 길이
+const MAX_KEY_LENGTH: usize = 256;
+
+/// `HKEY_LOCAL_MACHINE`을 읽는 레지스트리
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsRegistry;
+
+impl Registry for WindowsRegistry {
+    fn subkeys(&self, key: &str) -> Vec<String> {
+        let mut hkey = HKEY::default();
+        let opened = unsafe {
+            RegOpenKeyExW(
+                HKEY_LOCAL_MACHINE,
+                &HSTRING::from(key),
+                None,
+                KEY_READ,
+                &mut hkey,
+            )
+        };
+        if opened.is_err() {
+            return Vec::new();
+        }
+
+        let mut subkeys = Vec::new();
+        let mut buffer = [0u16; MAX_KEY_LENGTH];
+        for index in 0.. {
+            let mut length = buffer.len() as u32;
+            let result = unsafe {
+                RegEnumKeyExW(
+                    hkey,
+                    index,
+                    Some(PWSTR(buffer.as_mut_ptr())),
+                    &mut length,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+            };
+            if result.is_err() {
+                break;
+            }
+            subkeys.push(String::from_utf16_lossy(&buffer[..length as usize]));
+        }
+        let _ = unsafe { RegCloseKey(hkey) };
+
+        subkeys
+    }
+
+    fn value(&self, key: &str, name: &str) -> Option<String> {
+        let key = HSTRING::from(key);
+        let name = HSTRING::from(name);
+
+        // 크기를 먼저 얻고 다시 읽음
+        let mut size = 0u32;
+        unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                &key,
+                &name,
+                RRF_RT_REG_SZ,
+                None,
+                None,
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .ok()?;
+        let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+        unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                &key,
+                &name,
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .ok()?;
+
+        let length = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..length]))
+    }
+}
@@ -0,0 +1,156 @@
+//! 실행 파일 지문
+//!
+//! 게임 패치로 StarCraft.exe가 바뀌면 중복 실행 방지 뮤텍스 이름도 바뀔 수 있다. 실행 파일의
+//! 크기, 수정 시각, SHA-256, 버전을 설정에 저장해 두고 실행 전에 비교한다.
+
+use std::{
+    fs::{self, Metadata},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::pe::{self, Version};
+
+/// 실행 파일 하나의 지문
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Fingerprint {
+    /// 파일 크기(바이트)
+    pub size: u64,
+    /// 수정 시각(유닉스 시간)
+    pub modified: u64,
+    /// 내용의 SHA-256, 소문자 16진수
+    pub sha256: String,
+    /// 버전 리소스의 버전, 없으면 `None`
+    pub version: Option<Version>,
+}
+
+impl Fingerprint {
+    /// `path`의 파일을 읽어 지문을 만듦
+    pub fn of(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path).map_err(|err| err.to_string())?;
+        let bytes = fs::read(path).map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            size: bytes.len() as u64,
+            modified: modified(&metadata),
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+            version: pe::parse(&bytes).ok().and_then(|info| info.version()),
+        })
+    }
+
+    /// 로그에 쓸 짧은 이름, 버전을 모르면 해시 앞부분
+    pub fn label(&self) -> String {
+        match self.version {
+            Some(version) => version.to_string(),
+            None => format!("sha256 {}", &self.sha256[..self.sha256.len().min(12)]),
+        }
+    }
+}
+
+/// [`check`]의 결과
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// 저장된 지문과 같음
+    Unchanged,
+    /// 처음 보거나 내용은 같고 수정 시각만 바뀜, 새 지문을 저장하면 됨
+    Recorded(Fingerprint),
+    /// 내용이 바뀜
+    Updated {
+        /// 저장되어 있던 지문
+        previous: Fingerprint,
+        /// 지금 파일의 지문
+        current: Fingerprint,
+    },
+}
+
+/// `path`의 파일을 저장된 지문 `stored`와 비교
+///
+/// 크기와 수정 시각이 같으면 내용을 다시 읽지 않는다.
+pub fn check(path: impl AsRef<Path>, stored: Option<&Fingerprint>) -> Result<Check, String> {
+    let path = path.as_ref();
+    if let Some(stored) = stored {
+        let metadata = fs::metadata(path).map_err(|err| err.to_string())?;
+        if metadata.len() == stored.size && modified(&metadata) == stored.modified {
+            return Ok(Check::Unchanged);
+        }
+    }
+
+    let current = Fingerprint::of(path)?;
+    Ok(match stored {
+        Some(previous) if previous.sha256 != current.sha256 => Check::Updated {
+            previous: previous.clone(),
+            current,
+        },
+        _ => Check::Recorded(current),
+    })
+}
+
+// 수정 시각을 알 수 없으면 0
+fn modified(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("scr-fingerprint-{name}-{}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn fingerprint_hashes_contents() {
+        let path = temp_file("hash", b"abc");
+
+        let fingerprint = Fingerprint::of(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(fingerprint.size, 3);
+        assert_eq!(
+            fingerprint.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(fingerprint.version, None);
+        assert_eq!(fingerprint.label(), "sha256 ba7816bf8f01");
+    }
+
+    #[test]
+    fn check_detects_changed_contents() {
+        let path = temp_file("check", b"before");
+        let before = Fingerprint::of(&path).unwrap();
+
+        let unchanged = check(&path, Some(&before)).unwrap();
+        let first = check(&path, None).unwrap();
+        // 수정 시각만 다르면 내용을 비교해 다시 저장
+        let touched = Fingerprint {
+            modified: 0,
+            ..before.clone()
+        };
+        let retouched = check(&path, Some(&touched)).unwrap();
+        fs::write(&path, b"patched").unwrap();
+        let updated = check(&path, Some(&before)).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(unchanged, Check::Unchanged);
+        assert_eq!(first, Check::Recorded(before.clone()));
+        assert_eq!(retouched, Check::Recorded(before.clone()));
+        let Check::Updated { previous, current } = updated else {
+            panic!("{updated:?}");
+        };
+        assert_eq!(previous, before);
+        assert_ne!(current.sha256, before.sha256);
+    }
+}
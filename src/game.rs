@@ -0,0 +1,151 @@
+//! `game.toml` 게임 정의
+//!
+//! 찾을 실행 파일 이름과 닫을 중복 실행 방지 객체 이름을 파일로 바꿀 수 있어, 같은 런처로
+//! 1.16 클라이언트나 테스트 빌드처럼 단일 실행 잠금을 쓰는 다른 클라이언트도 다룰 수 있다.
+//! 파일이 없으면 StarCraft: Remastered 정의를 쓴다.
+
+use std::{fs, io::ErrorKind, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// 게임 정의 파일 위치
+pub const GAME_PATH: &str = "./game.toml";
+
+/// StarCraft: Remastered의 중복 실행 방지 뮤텍스 이름
+pub const MUTEX_NAME: &str = "Starcraft Check For Other Instances";
+
+/// 뮤텍스의 커널 객체 종류 이름
+pub const MUTANT_TYPE: &str = "Mutant";
+
+/// 런처가 다루는 게임, 빠진 항목은 StarCraft: Remastered 값
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct GameDefinition {
+    /// 창 제목과 상태 출력에 쓰는 게임 이름
+    pub display_name: String,
+    /// 클라이언트 실행 파일 이름, 대소문자를 구분하지 않음
+    pub image_names: Vec<String>,
+    /// 닫을 뮤텍스나 이벤트 이름, 객체 이름에 들어 있으면 닫음
+    pub lock_names: Vec<String>,
+    /// 이름을 확인할 핸들의 객체 종류 (`Mutant`, `Event` 등)
+    ///
+    /// 이벤트로 중복 실행을 막는 게임만 `Event`를 더한다. 시스템에 있는 종류와 하나도 맞지
+    /// 않으면 모든 핸들의 이름을 확인한다.
+    pub lock_types: Vec<String>,
+    /// 새 프로필의 기본 실행 인자
+    pub default_args: Vec<String>,
+}
+
+impl Default for GameDefinition {
+    fn default() -> Self {
+        Self {
+            display_name: "StarCraft: Remastered".to_string(),
+            image_names: vec!["StarCraft.exe".to_string()],
+            lock_names: vec![MUTEX_NAME.to_string()],
+            lock_types: vec![MUTANT_TYPE.to_string()],
+            default_args: vec!["-launch".to_string()],
+        }
+    }
+}
+
+impl GameDefinition {
+    /// `game.toml`을 읽고, 없으면 `None`
+    pub fn load() -> Result<Option<Self>, String> {
+        Self::load_from(GAME_PATH)
+    }
+
+    /// `path`의 게임 정의를 읽고, 없으면 `None`
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Option<Self>, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.to_string()),
+        };
+        let game: Self = toml::from_str(&text).map_err(|err| err.to_string())?;
+        if game.image_names.is_empty() {
+            return Err("image_names가 비어 있습니다.".to_string());
+        }
+
+        Ok(Some(game))
+    }
+
+    /// `image_name`이 이 게임의 클라이언트 실행 파일인지
+    pub fn is_client(&self, image_name: &str) -> bool {
+        self.image_names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(image_name))
+    }
+
+    /// 객체 종류 `type_name`의 핸들에서 중복 실행 방지 객체를 찾는지
+    pub fn is_lock_type(&self, type_name: &str) -> bool {
+        self.lock_types
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(type_name))
+    }
+
+    /// 객체 이름 `object_name`이 닫을 중복 실행 방지 객체인지
+    pub fn is_lock(&self, object_name: &str) -> bool {
+        self.lock_names
+            .iter()
+            .any(|name| !name.is_empty() && object_name.contains(name.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn missing_fields_keep_starcraft_values() {
+        let game: GameDefinition = toml::from_str(
+            r#"
+            display_name = "StarCraft (test build)"
+            image_names = ["StarCraft.exe", "StarCraft_test.exe"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            game,
+            GameDefinition {
+                display_name: "StarCraft (test build)".to_string(),
+                image_names: vec![
+                    "StarCraft.exe".to_string(),
+                    "StarCraft_test.exe".to_string()
+                ],
+                ..Default::default()
+            }
+        );
+        assert!(game.is_client("starcraft_TEST.exe"));
+        assert!(!game.is_client("Battle.net.exe"));
+        assert!(game.is_lock(&format!("\\Sessions\\1\\BaseNamedObjects\\{MUTEX_NAME}")));
+        assert!(!game.is_lock("\\Device\\HarddiskVolume3"));
+    }
+
+    #[test]
+    fn loads_definition_file() {
+        let dir = env::temp_dir().join(format!("scr-game-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.toml");
+
+        assert_eq!(GameDefinition::load_from(&path), Ok(None));
+        fs::write(
+            &path,
+            "lock_names = [\"Other Lock\"]\nlock_types = [\"Mutant\", \"Event\"]\n",
+        )
+        .unwrap();
+        let game = GameDefinition::load_from(&path).unwrap().unwrap();
+        assert!(game.is_lock("\\BaseNamedObjects\\Other Lock"));
+        assert!(!game.is_lock(MUTEX_NAME));
+        assert!(game.is_lock_type("Event"));
+        assert!(game.is_lock_type("Mutant"));
+        fs::write(&path, "image_names = []\n").unwrap();
+        assert!(GameDefinition::load_from(&path).is_err());
+        fs::write(&path, "image_names = \"StarCraft.exe\"\n").unwrap();
+        assert!(GameDefinition::load_from(&path).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,463 @@
+//! StarCraft: Remastered 다중 실행 라이브러리
+//!
+//! 실행 중인 StarCraft.exe를 찾고, 중복 실행 방지 뮤텍스를 닫고, 클라이언트를 실행하거나
+//! 종료하는 기능을 [`ProcessBackend`]로 제공한다. GUI와 명령줄 인터페이스는 이 라이브러리 위에
+//! 만들어진 바이너리다.
+//!
+//! ```no_run
+//! use scr_multi_launcher::{LaunchRequest, ProcessBackend, SystemBackend, wait_mutex};
+//!
+//! let backend = SystemBackend::default();
+//! let request = LaunchRequest::new("C:\\Program Files (x86)\\StarCraft\\x86_64\\StarCraft.exe");
+//! if let Some((pid, handle)) = backend.spawn(&request) {
+//!     if let Some(event) = wait_mutex(&backend, pid, handle) {
+//!         println!("{}", event.describe(backend.game()));
+//!     }
+//! }
+//! ```
+#![warn(missing_docs)]
+
+pub mod batch;
+pub mod battle_net;
+pub mod discovery;
+pub mod fingerprint;
+pub mod game;
+pub mod pe;
+pub mod product_db;
+pub mod scr;
+pub mod settings;
+pub mod template;
+
+use std::{cmp::Ordering, collections::BTreeMap, fmt};
+
+use chrono::{DateTime, Local};
+
+use crate::{game::GameDefinition, settings::Isolation};
+
+pub use scr::{NamedHandle, ProcessBackend, SystemBackend, query_child, wait_mutex};
+pub use settings::Settings;
+
+/// 프로그램 이름, 중복 실행 방지 뮤텍스 이름으로도 사용
+pub const APP_NAME: &str = "SC:R Multi-Launcher";
+
+/// 인스턴스를 구별하는 PID와 생성 시각
+pub type InstanceId = (u32, Option<u64>);
+
+/// 실행 중인 StarCraft 인스턴스
+#[derive(Debug, Clone)]
+pub struct SCRStruct {
+    /// 프로세스 ID
+    pub pid: u32,
+    /// 부모 프로세스 ID, 모르면 `None`
+    pub parent_pid: Option<u32>,
+    /// 프로세스 생성 시각, 모르면 `None`
+    ///
+    /// 백엔드마다 단위가 다르다. (Windows는 FILETIME, Linux는 부팅 후 클럭 틱) 같은 PID라도 생성
+    /// 시각이 다르면 PID가 재사용된 다른 프로세스다.
+    pub created: Option<u64>,
+    // 지금까지 거친 상태, 마지막 항목이 지금 상태
+    history: Vec<Transition>,
+}
+
+// Eq + PartialEq: pid와 생성 시각만 비교
+impl PartialEq for SCRStruct {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+impl Eq for SCRStruct {}
+
+// Ord + PartialOrd: pid와 생성 시각만 비교
+impl PartialOrd for SCRStruct {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for SCRStruct {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id().cmp(&other.id())
+    }
+}
+
+impl SCRStruct {
+    /// 아직 상태가 없는 인스턴스
+    pub fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            parent_pid: None,
+            created: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// 인스턴스를 구별하는 PID와 생성 시각
+    pub fn id(&self) -> InstanceId {
+        (self.pid, self.created)
+    }
+
+    /// 부모 프로세스를 아는 인스턴스
+    pub fn with_parent(pid: u32, parent_pid: u32) -> Self {
+        Self {
+            parent_pid: Some(parent_pid),
+            ..Self::new(pid)
+        }
+    }
+
+    /// 지금 상태, 아직 아무 일도 없었으면 `None`
+    pub fn state(&self) -> Option<State> {
+        self.history.last().map(|transition| transition.state)
+    }
+
+    /// 지금까지 거친 상태와 바뀐 시각
+    pub fn history(&self) -> &[Transition] {
+        &self.history
+    }
+
+    /// 끝난 상태인지
+    pub fn is_finished(&self) -> bool {
+        self.state().is_some_and(State::is_final)
+    }
+
+    /// 지금 `event`가 일어났을 때 상태를 바꾸고, 바뀌었으면 `true`
+    pub fn apply(&mut self, event: &Event) -> bool {
+        self.apply_at(event, Local::now())
+    }
+
+    /// `at`에 `event`가 일어났을 때 상태를 바꾸고, 바뀌었으면 `true`
+    ///
+    /// 다른 인스턴스의 사건, 지금과 같은 상태, [`State::can_become`]이 허용하지 않는 변화는
+    /// 무시한다.
+    pub fn apply_at(&mut self, event: &Event, at: DateTime<Local>) -> bool {
+        let next = event.state();
+        let allowed = match self.state() {
+            Some(current) => current.can_become(next),
+            None => true,
+        };
+        if event.pid() != self.pid || !allowed {
+            return false;
+        }
+        self.history.push(Transition { state: next, at });
+
+        true
+    }
+}
+
+/// 인스턴스의 수명 주기 상태
+///
+/// 런처가 실행한 인스턴스는 `Launching`, 프로세스 목록에서 찾은 인스턴스는 `WaitingForMutex`에서
+/// 시작해 `MutexClosed`를 거쳐 `Exited`, `Crashed`, `Terminated` 중 하나로 끝난다. 프로세스를 열
+/// 수 없으면 `OpenFailed`에서 다시 시도한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// 런처가 실행했고 아직 프로세스 목록에서 찾지 못함
+    Launching,
+    /// 찾았고 중복 실행 방지 뮤텍스를 닫기를 기다림
+    WaitingForMutex,
+    /// 프로세스를 열 수 없음, 다음 확인 때 다시 시도
+    OpenFailed,
+    /// 중복 실행 방지 뮤텍스를 닫음
+    MutexClosed,
+    /// 스스로 종료함, 종료 코드를 모르면 정상 종료로 봄
+    Exited,
+    /// 0이 아닌 종료 코드로 끝남
+    Crashed(u32),
+    /// 런처가 강제 종료함
+    Terminated,
+}
+
+impl State {
+    /// 더 이상 바뀌지 않는 상태인지
+    pub fn is_final(self) -> bool {
+        matches!(self, State::Exited | State::Crashed(_) | State::Terminated)
+    }
+
+    /// `self`에서 `next`로 바뀔 수 있는지
+    ///
+    /// 끝난 상태에서는 바뀌지 않고, `Launching`으로 돌아가거나 뮤텍스를 닫은 뒤 다시 기다리지
+    /// 않는다.
+    pub fn can_become(self, next: State) -> bool {
+        match (self, next) {
+            (current, _) if current.is_final() => false,
+            (_, State::Launching) => false,
+            (State::MutexClosed, State::WaitingForMutex | State::OpenFailed) => false,
+            (current, next) => current != next,
+        }
+    }
+}
+
+// 메인 화면에 표시하는 이름
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Launching => write!(f, "Launching"),
+            State::WaitingForMutex => write!(f, "Waiting for mutex"),
+            State::OpenFailed => write!(f, "Cannot open"),
+            State::MutexClosed => write!(f, "Mutex closed"),
+            State::Exited => write!(f, "Exited"),
+            State::Crashed(code) => write!(f, "Crashed ({code:#x})"),
+            State::Terminated => write!(f, "Terminated"),
+        }
+    }
+}
+
+/// 상태가 바뀐 기록
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    /// 바뀐 상태
+    pub state: State,
+    /// 바뀐 시각
+    pub at: DateTime<Local>,
+}
+
+/// 클라이언트 실행 요청
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchRequest {
+    /// StarCraft.exe 경로
+    pub path: String,
+    /// 실행 파일 뒤에 붙는 인자
+    pub args: Vec<String>,
+    /// 작업 디렉터리, `None`이면 실행 파일이 있는 폴더
+    pub working_dir: Option<String>,
+    /// 런처의 환경 변수에 더하거나 덮어쓸 환경 변수
+    pub env: BTreeMap<String, String>,
+    /// wine에서 실행하는 방법, `None`이면 백엔드의 wine 설정을 따름
+    pub isolation: Option<Isolation>,
+}
+
+impl LaunchRequest {
+    /// `-launch` 인자로 `path`를 실행하는 요청
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            args: vec!["-launch".to_string()],
+            working_dir: None,
+            env: BTreeMap::new(),
+            isolation: None,
+        }
+    }
+
+    /// 실제로 사용할 작업 디렉터리
+    ///
+    /// `path`는 Windows 경로일 수 있으므로 `\`와 `/`를 모두 구분자로 본다. 폴더를 알 수 없으면
+    /// `None`.
+    pub fn current_dir(&self) -> Option<String> {
+        if let Some(dir) = &self.working_dir {
+            return Some(dir.clone());
+        }
+
+        let end = self.path.rfind(['\\', '/'])?;
+        let dir = &self.path[..end];
+        // `C:\StarCraft.exe`와 `/StarCraft.exe`의 폴더는 루트
+        if dir.is_empty() || dir.ends_with(':') {
+            Some(self.path[..=end].to_string())
+        } else {
+            Some(dir.to_string())
+        }
+    }
+}
+
+/// 인스턴스에 일어난 일, 로그 문구는 [`describe`](Event::describe)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// 클라이언트를 실행함
+    Launched {
+        /// 프로세스 ID
+        pid: u32,
+        /// 실행 파일 버전, 모르면 `None`
+        version: Option<pe::Version>,
+    },
+    /// 프로세스 목록에서 찾음
+    Found {
+        /// 프로세스 ID
+        pid: u32,
+    },
+    /// 프로세스를 열 수 없음
+    OpenFailed {
+        /// 프로세스 ID
+        pid: u32,
+    },
+    /// 중복 실행 방지 뮤텍스를 닫음
+    MutexClosed {
+        /// 프로세스 ID
+        pid: u32,
+        /// 대상 프로세스 안의 핸들 값
+        handle: usize,
+    },
+    /// 프로세스가 끝남
+    Exited {
+        /// 프로세스 ID
+        pid: u32,
+        /// 종료 코드, 모르면 `None`
+        code: Option<u32>,
+    },
+    /// 프로세스를 강제 종료함
+    Terminated {
+        /// 프로세스 ID
+        pid: u32,
+    },
+}
+
+impl Event {
+    /// 사건이 일어난 프로세스 ID
+    pub fn pid(&self) -> u32 {
+        match *self {
+            Event::Launched { pid, .. }
+            | Event::Found { pid }
+            | Event::OpenFailed { pid }
+            | Event::MutexClosed { pid, .. }
+            | Event::Exited { pid, .. }
+            | Event::Terminated { pid } => pid,
+        }
+    }
+
+    /// 사건이 일어난 뒤의 인스턴스 상태
+    pub fn state(&self) -> State {
+        match *self {
+            Event::Launched { .. } => State::Launching,
+            Event::Found { .. } => State::WaitingForMutex,
+            Event::OpenFailed { .. } => State::OpenFailed,
+            Event::MutexClosed { .. } => State::MutexClosed,
+            Event::Exited {
+                code: Some(code), ..
+            } if code != 0 => State::Crashed(code),
+            Event::Exited { .. } => State::Exited,
+            Event::Terminated { .. } => State::Terminated,
+        }
+    }
+
+    /// 로그 문구, 클라이언트는 `game`의 이름으로 부름
+    pub fn describe(&self, game: &GameDefinition) -> String {
+        let name = &game.display_name;
+        match self {
+            Event::Launched {
+                pid,
+                version: Some(version),
+            } => format!("Launched {name} {version} (PID: {pid})"),
+            Event::Launched { pid, version: None } => format!("Launched {name} (PID: {pid})"),
+            Event::Found { pid } => format!("Found {name} (PID: {pid})"),
+            Event::OpenFailed { pid } => format!("Failed to open {name} (PID: {pid})"),
+            Event::MutexClosed { pid, handle } => {
+                format!("Closed HANDLE({handle:#x}) for {name} (PID: {pid})")
+            }
+            // 종료 코드를 모르면 프로세스를 더 이상 열 수 없다는 것만 앎
+            Event::Exited { pid, code: None } => format!("Invalid PID: {pid}"),
+            Event::Exited { pid, code: Some(0) } => format!("{name} (PID: {pid}) exited"),
+            Event::Exited {
+                pid,
+                code: Some(code),
+            } => format!("{name} (PID: {pid}) crashed with exit code {code:#x}"),
+            Event::Terminated { pid } => format!("Successfully terminated process wid PID {pid}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_dir_defaults_to_exe_folder() {
+        let dir = |path| LaunchRequest::new(path).current_dir();
+
+        assert_eq!(
+            dir("C:\\Program Files (x86)\\StarCraft\\x86_64\\StarCraft.exe").as_deref(),
+            Some("C:\\Program Files (x86)\\StarCraft\\x86_64")
+        );
+        assert_eq!(dir("C:\\StarCraft.exe").as_deref(), Some("C:\\"));
+        assert_eq!(dir("/games/StarCraft.exe").as_deref(), Some("/games"));
+        assert_eq!(dir("/StarCraft.exe").as_deref(), Some("/"));
+        assert_eq!(dir("StarCraft.exe"), None);
+
+        let request = LaunchRequest {
+            working_dir: Some("D:\\Replays".to_string()),
+            ..LaunchRequest::new("C:\\StarCraft\\StarCraft.exe")
+        };
+        assert_eq!(request.current_dir().as_deref(), Some("D:\\Replays"));
+    }
+
+    #[test]
+    fn instance_follows_lifecycle() {
+        let at = Local::now();
+        let mut child = SCRStruct::new(42);
+
+        let launched = child.apply_at(
+            &Event::Launched {
+                pid: 42,
+                version: None,
+            },
+            at,
+        );
+        let found = child.apply_at(&Event::Found { pid: 42 }, at);
+        let found_again = child.apply_at(&Event::Found { pid: 42 }, at);
+        let other = child.apply_at(&Event::Terminated { pid: 43 }, at);
+        let closed = child.apply_at(
+            &Event::MutexClosed {
+                pid: 42,
+                handle: 0x2c,
+            },
+            at,
+        );
+        let reopened = child.apply_at(&Event::OpenFailed { pid: 42 }, at);
+        let crashed = child.apply_at(
+            &Event::Exited {
+                pid: 42,
+                code: Some(0xc0000005),
+            },
+            at,
+        );
+        let terminated = child.apply_at(&Event::Terminated { pid: 42 }, at);
+
+        assert_eq!(
+            [
+                launched,
+                found,
+                found_again,
+                other,
+                closed,
+                reopened,
+                crashed,
+                terminated
+            ],
+            [true, true, false, false, true, false, true, false]
+        );
+        assert_eq!(
+            child
+                .history()
+                .iter()
+                .map(|transition| transition.state)
+                .collect::<Vec<_>>(),
+            [
+                State::Launching,
+                State::WaitingForMutex,
+                State::MutexClosed,
+                State::Crashed(0xc0000005),
+            ]
+        );
+        assert!(child.is_finished());
+    }
+
+    #[test]
+    fn open_failure_is_retried() {
+        let mut child = SCRStruct::new(42);
+
+        child.apply(&Event::Found { pid: 42 });
+        child.apply(&Event::OpenFailed { pid: 42 });
+        let recovered = child.apply(&Event::MutexClosed {
+            pid: 42,
+            handle: 0x2c,
+        });
+
+        assert!(recovered);
+        assert_eq!(child.state(), Some(State::MutexClosed));
+        assert!(!State::Launching.can_become(State::Launching));
+        assert!(State::OpenFailed.can_become(State::Exited));
+        assert_eq!(
+            Event::Exited {
+                pid: 42,
+                code: Some(0)
+            }
+            .state(),
+            State::Exited
+        );
+    }
+}
@@ -1,64 +1,68 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
-mod scr;
-mod settings;
-
-use std::cmp::Ordering;
+mod cli;
 
+use clap::Parser;
 use iced::{Font, Task};
+use scr_multi_launcher::{
+    ProcessBackend, Settings, SystemBackend,
+    game::GameDefinition,
+    scr::{attach_console, get_mutex},
+};
 
-use crate::{app::App, scr::get_mutex, settings::Settings};
+use crate::{
+    app::{App, Message},
+    cli::Cli,
+};
 
-const APP_NAME: &str = "SC:R Multi-Launcher";
 const KOREAN_FONT: Font = Font::with_name("Malgun Gothic");
 
-#[derive(Debug, Clone)]
-struct SCRStruct {
-    pid: u32,
-    is_processed: bool,
-}
-
-// Eq + PartialEq: pid만 비교
-impl PartialEq for SCRStruct {
-    fn eq(&self, other: &Self) -> bool {
-        self.pid == other.pid
-    }
-}
-impl Eq for SCRStruct {}
-
-// Ord + PartialOrd: pid만 비교
-impl PartialOrd for SCRStruct {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.pid.cmp(&other.pid))
-    }
-}
-impl Ord for SCRStruct {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.pid.cmp(&other.pid)
-    }
-}
-
-impl SCRStruct {
-    fn new(pid: u32) -> Self {
-        Self {
-            pid,
-            is_processed: false,
+fn main() -> iced::Result {
+    // 릴리스 빌드는 콘솔이 없으므로 도움말과 오류는 콘솔을 연결한 뒤 출력
+    let cli = Cli::try_parse().unwrap_or_else(|err| {
+        attach_console();
+        err.exit()
+    });
+    if let Some(command) = cli.command {
+        attach_console();
+        let maybe_settings = tokio::runtime::Runtime::new()
+            .ok()
+            .and_then(|runtime| runtime.block_on(Settings::load()));
+        let mut backend = SystemBackend::default();
+        match GameDefinition::load() {
+            Ok(maybe_game) => backend.set_game(maybe_game.unwrap_or_default()),
+            Err(err) => {
+                eprintln!("game.toml: {err}");
+                std::process::exit(1);
+            }
         }
+        let result = cli::run(backend, maybe_settings, command, &mut std::io::stdout());
+        if let Err(err) = result {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+
+        return Ok(());
     }
-}
 
-fn main() -> iced::Result {
     if get_mutex() {
-        iced::application(APP_NAME, App::update, App::view)
+        iced::application(App::title, App::update, App::view)
             .subscription(App::subscription)
             .default_font(KOREAN_FONT)
-            .window_size((400.0, 300.0))
+            .window_size((400.0, 400.0))
             .resizable(false)
             .run_with(|| {
                 let maybe_settings = iced::futures::executor::block_on(Settings::load());
 
-                (App::new(maybe_settings), Task::none())
+                (
+                    App::new(
+                        SystemBackend::default(),
+                        maybe_settings,
+                        GameDefinition::load(),
+                    ),
+                    Task::done(Message::CheckExecutables),
+                )
             })
     } else {
         Ok(())
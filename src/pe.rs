@@ -0,0 +1,648 @@
+//! PE(Portable Executable) 헤더 읽기
+//!
+//! 설정한 파일이 Windows 실행 파일인지 확인하고 아키텍처, 서브시스템, 빌드 시각과
+//! `VS_VERSIONINFO` 리소스의 버전을 읽는다. Win32 API 없이 바이트만 해석하므로 모든 OS에서
+//! 동작한다.
+
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Arch;
+
+const IMAGE_FILE_MACHINE_I386: u16 = 0x014c;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_EXECUTABLE_IMAGE: u16 = 0x0002;
+const IMAGE_FILE_DLL: u16 = 0x2000;
+const IMAGE_SUBSYSTEM_WINDOWS_GUI: u16 = 2;
+const IMAGE_SUBSYSTEM_WINDOWS_CUI: u16 = 3;
+const PE32_MAGIC: u16 = 0x010b;
+const PE32_PLUS_MAGIC: u16 = 0x020b;
+const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+const RT_VERSION: u32 = 16;
+const VS_FFI_SIGNATURE: u32 = 0xfeef_04bd;
+// 리소스 디렉터리 항목의 OffsetToData가 하위 디렉터리를 가리킴
+const RESOURCE_SUBDIRECTORY: u32 = 0x8000_0000;
+
+// DOS 헤더 안의 PE 헤더 위치
+const E_LFANEW_OFFSET: usize = 0x3c;
+const COFF_HEADER_SIZE: usize = 20;
+// 선택적 헤더 안의 Subsystem 위치, PE32와 PE32+가 같음
+const SUBSYSTEM_OFFSET: usize = 68;
+// 선택적 헤더 안의 NumberOfRvaAndSizes 위치, 데이터 디렉터리가 바로 뒤에 옴
+const PE32_RVA_COUNT_OFFSET: usize = 92;
+const PE32_PLUS_RVA_COUNT_OFFSET: usize = 108;
+const SECTION_HEADER_SIZE: usize = 40;
+const RESOURCE_DIRECTORY_SIZE: usize = 16;
+const VS_FIXEDFILEINFO_SIZE: usize = 52;
+
+/// COFF 헤더의 대상 CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    /// 32비트 x86
+    I386,
+    /// 64비트 x86
+    Amd64,
+    /// 그 밖의 값
+    Other(u16),
+}
+
+impl Machine {
+    /// StarCraft가 제공하는 아키텍처면 `Some`
+    pub fn arch(self) -> Option<Arch> {
+        match self {
+            Machine::I386 => Some(Arch::X86),
+            Machine::Amd64 => Some(Arch::X64),
+            Machine::Other(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Machine::I386 => write!(f, "i386"),
+            Machine::Amd64 => write!(f, "AMD64"),
+            Machine::Other(value) => write!(f, "machine {value:#06x}"),
+        }
+    }
+}
+
+/// 선택적 헤더의 서브시스템
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    /// 창을 쓰는 프로그램
+    Gui,
+    /// 콘솔 프로그램
+    Console,
+    /// 그 밖의 값
+    Other(u16),
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subsystem::Gui => write!(f, "GUI"),
+            Subsystem::Console => write!(f, "Console"),
+            Subsystem::Other(value) => write!(f, "subsystem {value}"),
+        }
+    }
+}
+
+/// `VS_FIXEDFILEINFO`의 `a.b.c.d` 버전, 설정 파일에는 문자열로 저장
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(into = "String", try_from = "String")]
+pub struct Version {
+    /// 주 버전
+    pub major: u16,
+    /// 부 버전
+    pub minor: u16,
+    /// 패치
+    pub patch: u16,
+    /// 빌드 번호
+    pub build: u16,
+}
+
+impl Version {
+    fn from_parts(most: u32, least: u32) -> Self {
+        Self {
+            major: (most >> 16) as u16,
+            minor: most as u16,
+            patch: (least >> 16) as u16,
+            build: least as u16,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Option<Vec<u16>> = s.split('.').map(|part| part.parse().ok()).collect();
+        match parts.as_deref() {
+            Some(&[major, minor, patch, build]) => Ok(Self {
+                major,
+                minor,
+                patch,
+                build,
+            }),
+            _ => Err(format!("{s}: 버전 형식이 아닙니다.")),
+        }
+    }
+}
+
+impl TryFrom<String> for Version {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Version> for String {
+    fn from(version: Version) -> Self {
+        version.to_string()
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.patch, self.build
+        )
+    }
+}
+
+/// 실행 파일 헤더에서 읽은 정보
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeInfo {
+    /// 대상 CPU
+    pub machine: Machine,
+    /// 서브시스템
+    pub subsystem: Subsystem,
+    /// 링커가 기록한 빌드 시각(유닉스 시간)
+    pub timestamp: u32,
+    /// 파일 버전, 버전 리소스가 없거나 손상되었으면 `None`
+    pub file_version: Option<Version>,
+    /// 제품 버전, 버전 리소스가 없거나 손상되었으면 `None`
+    pub product_version: Option<Version>,
+}
+
+impl PeInfo {
+    /// [`timestamp`](Self::timestamp)를 시각으로 바꾼 값
+    pub fn built_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.timestamp.into(), 0)
+    }
+
+    /// 표시할 버전, 파일 버전이 없으면 제품 버전
+    pub fn version(&self) -> Option<Version> {
+        self.file_version.or(self.product_version)
+    }
+}
+
+// 섹션 헤더에서 RVA를 파일 위치로 바꾸는 데 필요한 값
+struct Section {
+    virtual_address: u32,
+    raw_size: u32,
+    raw_offset: u32,
+}
+
+/// `path`의 실행 파일을 읽어 헤더를 해석
+pub fn inspect(path: impl AsRef<Path>) -> Result<PeInfo, String> {
+    let bytes = fs::read(path).map_err(|err| err.to_string())?;
+
+    parse(&bytes)
+}
+
+/// 실행 파일 내용을 해석, PE 실행 파일이 아니면 이유를 담은 `Err`
+pub fn parse(bytes: &[u8]) -> Result<PeInfo, String> {
+    if bytes.get(..2) != Some(b"MZ") {
+        return Err("MZ 시그니처가 없습니다.".to_string());
+    }
+    let pe = read_u32(bytes, E_LFANEW_OFFSET)? as usize;
+    if bytes.get(pe..pe.saturating_add(4)) != Some(b"PE\0\0") {
+        return Err("PE 시그니처가 없습니다.".to_string());
+    }
+
+    let coff = pe + 4;
+    let machine = match read_u16(bytes, coff)? {
+        IMAGE_FILE_MACHINE_I386 => Machine::I386,
+        IMAGE_FILE_MACHINE_AMD64 => Machine::Amd64,
+        value => Machine::Other(value),
+    };
+    let section_count = read_u16(bytes, coff + 2)? as usize;
+    let timestamp = read_u32(bytes, coff + 4)?;
+    let optional_size = read_u16(bytes, coff + 16)? as usize;
+    let characteristics = read_u16(bytes, coff + 18)?;
+    if characteristics & IMAGE_FILE_EXECUTABLE_IMAGE == 0 || characteristics & IMAGE_FILE_DLL != 0 {
+        return Err("실행 파일(.exe)이 아닙니다.".to_string());
+    }
+
+    let optional = coff + COFF_HEADER_SIZE;
+    let rva_count_offset = match read_u16(bytes, optional)? {
+        PE32_MAGIC => PE32_RVA_COUNT_OFFSET,
+        PE32_PLUS_MAGIC => PE32_PLUS_RVA_COUNT_OFFSET,
+        _ => return Err("선택적 헤더 형식을 알 수 없습니다.".to_string()),
+    };
+    if optional_size < SUBSYSTEM_OFFSET + 2 {
+        return Err("선택적 헤더가 너무 짧습니다.".to_string());
+    }
+    let subsystem = match read_u16(bytes, optional + SUBSYSTEM_OFFSET)? {
+        IMAGE_SUBSYSTEM_WINDOWS_GUI => Subsystem::Gui,
+        IMAGE_SUBSYSTEM_WINDOWS_CUI => Subsystem::Console,
+        value => Subsystem::Other(value),
+    };
+
+    // 버전 리소스는 없어도 실행 파일로는 문제없음
+    let sections: Vec<_> = (0..section_count)
+        .map_while(|index| {
+            section(
+                bytes,
+                optional + optional_size + index * SECTION_HEADER_SIZE,
+            )
+        })
+        .collect();
+    let fixed_info = (rva_count_offset + 4 + (IMAGE_DIRECTORY_ENTRY_RESOURCE + 1) * 8
+        <= optional_size)
+        .then(|| fixed_file_info(bytes, &sections, optional + rva_count_offset))
+        .flatten();
+
+    Ok(PeInfo {
+        machine,
+        subsystem,
+        timestamp,
+        file_version: fixed_info.map(|(file, _)| file),
+        product_version: fixed_info.map(|(_, product)| product),
+    })
+}
+
+fn section(bytes: &[u8], offset: usize) -> Option<Section> {
+    Some(Section {
+        virtual_address: read_u32(bytes, offset + 12).ok()?,
+        raw_size: read_u32(bytes, offset + 16).ok()?,
+        raw_offset: read_u32(bytes, offset + 20).ok()?,
+    })
+}
+
+fn rva_to_offset(sections: &[Section], rva: u32) -> Option<usize> {
+    sections.iter().find_map(|section| {
+        let delta = rva.checked_sub(section.virtual_address)?;
+        (delta < section.raw_size).then(|| section.raw_offset as usize + delta as usize)
+    })
+}
+
+// 리소스 디렉터리에서 `id`가 같은 항목(`None`이면 첫 항목)의 OffsetToData
+fn resource_entry(bytes: &[u8], directory: usize, id: Option<u32>) -> Option<u32> {
+    let named = read_u16(bytes, directory + 12).ok()? as usize;
+    let ids = read_u16(bytes, directory + 14).ok()? as usize;
+
+    (0..named + ids).find_map(|index| {
+        let entry = directory + RESOURCE_DIRECTORY_SIZE + index * 8;
+        let name = read_u32(bytes, entry).ok()?;
+        id.is_none_or(|id| id == name)
+            .then(|| read_u32(bytes, entry + 4).ok())
+            .flatten()
+    })
+}
+
+// 리소스 트리(종류 → 이름 → 언어)를 따라 첫 번째 버전 리소스의 (파일 버전, 제품 버전)
+fn fixed_file_info(
+    bytes: &[u8],
+    sections: &[Section],
+    rva_count: usize,
+) -> Option<(Version, Version)> {
+    if (read_u32(bytes, rva_count).ok()? as usize) <= IMAGE_DIRECTORY_ENTRY_RESOURCE {
+        return None;
+    }
+    let resource_rva = read_u32(bytes, rva_count + 4 + IMAGE_DIRECTORY_ENTRY_RESOURCE * 8).ok()?;
+    let root = rva_to_offset(sections, resource_rva)?;
+
+    let mut entry = resource_entry(bytes, root, Some(RT_VERSION))?;
+    for _ in 0..2 {
+        if entry & RESOURCE_SUBDIRECTORY == 0 {
+            return None;
+        }
+        let directory = root + (entry & !RESOURCE_SUBDIRECTORY) as usize;
+        entry = resource_entry(bytes, directory, None)?;
+    }
+    if entry & RESOURCE_SUBDIRECTORY != 0 {
+        return None;
+    }
+
+    let data_entry = root + entry as usize;
+    let data = rva_to_offset(sections, read_u32(bytes, data_entry).ok()?)?;
+    let size = read_u32(bytes, data_entry + 4).ok()? as usize;
+
+    version_info(bytes.get(data..data.checked_add(size)?)?)
+}
+
+// VS_VERSIONINFO의 Value인 VS_FIXEDFILEINFO에서 (파일 버전, 제품 버전)
+fn version_info(block: &[u8]) -> Option<(Version, Version)> {
+    let value_length = read_u16(block, 2).ok()? as usize;
+    let key: Vec<u16> = block
+        .get(6..)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    if String::from_utf16(&key).ok()? != "VS_VERSION_INFO" || value_length < VS_FIXEDFILEINFO_SIZE {
+        return None;
+    }
+
+    // 키와 끝의 NUL 다음, 4바이트 경계
+    let value = (6 + (key.len() + 1) * 2).next_multiple_of(4);
+    if read_u32(block, value).ok()? != VS_FFI_SIGNATURE {
+        return None;
+    }
+    let part = |index: usize| read_u32(block, value + 8 + index * 4).ok();
+
+    Some((
+        Version::from_parts(part(0)?, part(1)?),
+        Version::from_parts(part(2)?, part(3)?),
+    ))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, String> {
+    read_array(bytes, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, String> {
+    read_array(bytes, offset).map(u32::from_le_bytes)
+}
+
+fn read_array<const N: usize>(bytes: &[u8], offset: usize) -> Result<[u8; N], String> {
+    bytes
+        .get(offset..offset.saturating_add(N))
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or_else(|| "파일이 잘렸습니다.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 헤더와 버전 리소스만 있는 최소한의 실행 파일
+    struct Fixture {
+        machine: u16,
+        magic: u16,
+        characteristics: u16,
+        subsystem: u16,
+        timestamp: u32,
+        // (파일 버전, 제품 버전)의 (MS, LS)
+        version: Option<[(u32, u32); 2]>,
+    }
+
+    impl Default for Fixture {
+        fn default() -> Self {
+            Self {
+                machine: IMAGE_FILE_MACHINE_AMD64,
+                magic: PE32_PLUS_MAGIC,
+                characteristics: IMAGE_FILE_EXECUTABLE_IMAGE | 0x0020,
+                subsystem: IMAGE_SUBSYSTEM_WINDOWS_GUI,
+                timestamp: 1_700_000_000,
+                version: None,
+            }
+        }
+    }
+
+    impl Fixture {
+        const PE_OFFSET: usize = 0x80;
+        const RSRC_RVA: u32 = 0x1000;
+        const RSRC_OFFSET: usize = 0x400;
+        // .rsrc 섹션 안에서 버전 리소스 내용의 위치
+        const VERSION_INFO: usize = 88;
+
+        fn bytes(&self) -> Vec<u8> {
+            let optional_size: u16 = if self.magic == PE32_MAGIC { 224 } else { 240 };
+            let mut bytes = vec![0; Self::PE_OFFSET];
+            bytes[..2].copy_from_slice(b"MZ");
+            bytes[E_LFANEW_OFFSET..E_LFANEW_OFFSET + 4]
+                .copy_from_slice(&(Self::PE_OFFSET as u32).to_le_bytes());
+
+            bytes.extend_from_slice(b"PE\0\0");
+            bytes.extend_from_slice(&self.machine.to_le_bytes());
+            let section_count = u16::from(self.version.is_some());
+            bytes.extend_from_slice(&section_count.to_le_bytes());
+            bytes.extend_from_slice(&self.timestamp.to_le_bytes());
+            bytes.extend_from_slice(&[0; 8]);
+            bytes.extend_from_slice(&optional_size.to_le_bytes());
+            bytes.extend_from_slice(&self.characteristics.to_le_bytes());
+
+            let optional = bytes.len();
+            bytes.resize(optional + optional_size as usize, 0);
+            bytes[optional..optional + 2].copy_from_slice(&self.magic.to_le_bytes());
+            bytes[optional + SUBSYSTEM_OFFSET..optional + SUBSYSTEM_OFFSET + 2]
+                .copy_from_slice(&self.subsystem.to_le_bytes());
+
+            if let Some(version) = self.version {
+                let rsrc = Self::rsrc(version);
+                let rva_count = optional
+                    + if self.magic == PE32_MAGIC {
+                        PE32_RVA_COUNT_OFFSET
+                    } else {
+                        PE32_PLUS_RVA_COUNT_OFFSET
+                    };
+                let directory = rva_count + 4 + IMAGE_DIRECTORY_ENTRY_RESOURCE * 8;
+                put_u32(&mut bytes, rva_count, 16);
+                put_u32(&mut bytes, directory, Self::RSRC_RVA);
+                put_u32(&mut bytes, directory + 4, rsrc.len() as u32);
+
+                let section = bytes.len();
+                bytes.resize(section + SECTION_HEADER_SIZE, 0);
+                bytes[section..section + 5].copy_from_slice(b".rsrc");
+                put_u32(&mut bytes, section + 8, rsrc.len() as u32);
+                put_u32(&mut bytes, section + 12, Self::RSRC_RVA);
+                put_u32(&mut bytes, section + 16, rsrc.len() as u32);
+                put_u32(&mut bytes, section + 20, Self::RSRC_OFFSET as u32);
+
+                bytes.resize(Self::RSRC_OFFSET, 0);
+                bytes.extend_from_slice(&rsrc);
+            }
+
+            bytes
+        }
+
+        // RT_VERSION → 1 → 0x409 → 데이터 항목 → VS_VERSIONINFO
+        fn rsrc([(file_ms, file_ls), (product_ms, product_ls)]: [(u32, u32); 2]) -> Vec<u8> {
+            let mut rsrc = vec![0; Self::VERSION_INFO];
+            let levels = [(RT_VERSION, 24), (1, 48), (0x409, 72)];
+            for (index, (id, next)) in levels.into_iter().enumerate() {
+                let directory = index * 24;
+                rsrc[directory + 14..directory + 16].copy_from_slice(&1u16.to_le_bytes());
+                put_u32(&mut rsrc, directory + 16, id);
+                let subdirectory = if index < 2 { RESOURCE_SUBDIRECTORY } else { 0 };
+                put_u32(&mut rsrc, directory + 20, next | subdirectory);
+            }
+
+            let mut info = Vec::new();
+            info.extend_from_slice(&0u16.to_le_bytes()); // wLength
+            info.extend_from_slice(&(VS_FIXEDFILEINFO_SIZE as u16).to_le_bytes());
+            info.extend_from_slice(&0u16.to_le_bytes()); // wType
+            info.extend(
+                "VS_VERSION_INFO\0"
+                    .encode_utf16()
+                    .flat_map(u16::to_le_bytes),
+            );
+            info.resize(info.len().next_multiple_of(4), 0);
+            let fixed = [
+                VS_FFI_SIGNATURE,
+                0x0001_0000,
+                file_ms,
+                file_ls,
+                product_ms,
+                product_ls,
+            ];
+            info.extend(fixed.into_iter().flat_map(u32::to_le_bytes));
+            info.resize(info.len() + VS_FIXEDFILEINFO_SIZE - fixed.len() * 4, 0);
+            let length = info.len() as u16;
+            info[..2].copy_from_slice(&length.to_le_bytes());
+
+            put_u32(&mut rsrc, 72, Self::RSRC_RVA + Self::VERSION_INFO as u32);
+            put_u32(&mut rsrc, 76, info.len() as u32);
+            rsrc.extend_from_slice(&info);
+
+            rsrc
+        }
+    }
+
+    fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    // 1.23.10.13515
+    const SCR_VERSION: (u32, u32) = (0x0001_0017, 0x000a_34cb);
+
+    #[test]
+    fn reads_64bit_gui_executable() {
+        let info = parse(&Fixture::default().bytes()).unwrap();
+
+        assert_eq!(info.machine, Machine::Amd64);
+        assert_eq!(info.machine.arch(), Some(Arch::X64));
+        assert_eq!(info.subsystem, Subsystem::Gui);
+        assert_eq!(
+            info.built_at().unwrap().to_rfc3339(),
+            "2023-11-14T22:13:20+00:00"
+        );
+    }
+
+    #[test]
+    fn reads_32bit_console_executable() {
+        let fixture = Fixture {
+            machine: IMAGE_FILE_MACHINE_I386,
+            magic: PE32_MAGIC,
+            subsystem: IMAGE_SUBSYSTEM_WINDOWS_CUI,
+            ..Default::default()
+        };
+
+        let info = parse(&fixture.bytes()).unwrap();
+
+        assert_eq!(info.machine.arch(), Some(Arch::X86));
+        assert_eq!(info.subsystem, Subsystem::Console);
+    }
+
+    #[test]
+    fn unknown_machine_has_no_arch() {
+        let fixture = Fixture {
+            machine: 0xaa64,
+            ..Default::default()
+        };
+
+        let info = parse(&fixture.bytes()).unwrap();
+
+        assert_eq!(info.machine, Machine::Other(0xaa64));
+        assert_eq!(info.machine.arch(), None);
+        assert_eq!(info.machine.to_string(), "machine 0xaa64");
+    }
+
+    #[test]
+    fn rejects_non_pe_files() {
+        let valid = Fixture::default().bytes();
+        let mut no_pe_signature = valid.clone();
+        no_pe_signature[Fixture::PE_OFFSET] = b'X';
+        let mut far_e_lfanew = valid.clone();
+        far_e_lfanew[E_LFANEW_OFFSET..E_LFANEW_OFFSET + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let dll = Fixture {
+            characteristics: IMAGE_FILE_EXECUTABLE_IMAGE | IMAGE_FILE_DLL,
+            ..Default::default()
+        };
+        let object = Fixture {
+            characteristics: 0,
+            ..Default::default()
+        };
+        let rom = Fixture {
+            magic: 0x0107,
+            ..Default::default()
+        };
+
+        let cases = [
+            b"".to_vec(),
+            b"#!/bin/sh\necho StarCraft\n".to_vec(),
+            no_pe_signature,
+            far_e_lfanew,
+            valid[..Fixture::PE_OFFSET + 10].to_vec(),
+            valid[..Fixture::PE_OFFSET + 24 + SUBSYSTEM_OFFSET].to_vec(),
+            dll.bytes(),
+            object.bytes(),
+            rom.bytes(),
+        ];
+
+        for (index, bytes) in cases.iter().enumerate() {
+            assert!(parse(bytes).is_err(), "case {index}");
+        }
+    }
+
+    #[test]
+    fn reads_version_resource() {
+        for magic in [PE32_MAGIC, PE32_PLUS_MAGIC] {
+            let fixture = Fixture {
+                magic,
+                version: Some([SCR_VERSION, (0x0001_0017, 0)]),
+                ..Default::default()
+            };
+
+            let info = parse(&fixture.bytes()).unwrap();
+
+            assert_eq!(info.file_version.unwrap().to_string(), "1.23.10.13515");
+            assert_eq!(info.product_version.unwrap().to_string(), "1.23.0.0");
+            assert_eq!(info.version(), info.file_version);
+        }
+    }
+
+    #[test]
+    fn version_round_trips_through_string() {
+        let version: Version = "1.23.10.13515".parse().unwrap();
+
+        assert_eq!(version, Version::from_parts(SCR_VERSION.0, SCR_VERSION.1));
+        assert_eq!(String::from(version), "1.23.10.13515");
+        assert!("1.23.10".parse::<Version>().is_err());
+        assert!("1.23.10.70000".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn damaged_version_resource_is_ignored() {
+        let fixture = Fixture {
+            version: Some([SCR_VERSION; 2]),
+            ..Default::default()
+        };
+        let valid = fixture.bytes();
+        let info_offset = Fixture::RSRC_OFFSET + Fixture::VERSION_INFO;
+        let mut bad_signature = valid.clone();
+        bad_signature[info_offset + 40] ^= 0xff;
+        let mut bad_key = valid.clone();
+        bad_key[info_offset + 6] = b'X';
+        let mut subdirectory_loop = valid.clone();
+        put_u32(
+            &mut subdirectory_loop,
+            Fixture::RSRC_OFFSET + 20,
+            RESOURCE_SUBDIRECTORY,
+        );
+
+        let cases = [
+            Fixture::default().bytes(),
+            valid[..valid.len() - 8].to_vec(),
+            bad_signature,
+            bad_key,
+            subdirectory_loop,
+        ];
+
+        for (index, bytes) in cases.iter().enumerate() {
+            let info = parse(bytes).unwrap();
+            assert_eq!(info.version(), None, "case {index}");
+        }
+    }
+
+    #[test]
+    fn inspect_reads_file() {
+        let path = std::env::temp_dir().join(format!("scr-pe-{}.exe", std::process::id()));
+        fs::write(&path, Fixture::default().bytes()).unwrap();
+
+        let info = inspect(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(info.unwrap().machine, Machine::Amd64);
+        assert!(inspect("/nonexistent/StarCraft.exe").is_err());
+    }
+}
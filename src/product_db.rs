@@ -0,0 +1,211 @@
+//! Battle.net `product.db` 읽기
+//!
+//! Battle.net 에이전트는 설치한 게임마다 설치 경로와 언어를 `ProgramData/Battle.net/Agent/
+//! product.db`에 protobuf로 기록한다. 필요한 필드만 읽는 최소한의 디코더로 StarCraft:
+//! Remastered(`s1`)의 설치 폴더와 언어를 찾는다.
+
+/// StarCraft: Remastered의 제품 코드
+pub const STARCRAFT_PRODUCT: &str = "s1";
+
+/// `ProgramData` 아래의 product.db 위치
+pub const PRODUCT_DB_PATH: &str = "Battle.net/Agent/product.db";
+
+// Database.product_install
+const DATABASE_PRODUCT_INSTALL: u64 = 1;
+// ProductInstall의 필드
+const INSTALL_UID: u64 = 1;
+const INSTALL_PRODUCT_CODE: u64 = 2;
+const INSTALL_SETTINGS: u64 = 3;
+// UserSettings의 필드
+const SETTINGS_INSTALL_PATH: u64 = 1;
+const SETTINGS_TEXT_LANGUAGE: u64 = 6;
+
+/// product.db에 기록된 제품 설치 하나
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProductInstall {
+    /// 설치 ID, 같은 제품을 여러 번 설치하면 달라짐
+    pub uid: String,
+    /// 제품 코드, StarCraft: Remastered는 `s1`
+    pub product_code: String,
+    /// 설치 폴더, Battle.net은 `C:/Program Files (x86)/StarCraft`처럼 `/`를 씀
+    pub install_path: String,
+    /// 게임 텍스트 언어 (`koKR`, `enUS` 등)
+    pub locale: String,
+}
+
+/// product.db의 모든 제품 설치
+pub fn parse(bytes: &[u8]) -> Result<Vec<ProductInstall>, String> {
+    let mut installs = Vec::new();
+    let mut reader = Reader::new(bytes);
+    while let Some((field, value)) = reader.field()? {
+        if field == DATABASE_PRODUCT_INSTALL {
+            installs.push(product_install(value.bytes()?)?);
+        }
+    }
+
+    Ok(installs)
+}
+
+/// product.db에서 설치 경로가 있는 첫 번째 StarCraft: Remastered 설치
+pub fn starcraft(bytes: &[u8]) -> Result<Option<ProductInstall>, String> {
+    Ok(parse(bytes)?.into_iter().find(|install| {
+        install.product_code == STARCRAFT_PRODUCT && !install.install_path.is_empty()
+    }))
+}
+
+fn product_install(bytes: &[u8]) -> Result<ProductInstall, String> {
+    let mut install = ProductInstall::default();
+    let mut reader = Reader::new(bytes);
+    while let Some((field, value)) = reader.field()? {
+        match field {
+            INSTALL_UID => install.uid = value.string()?,
+            INSTALL_PRODUCT_CODE => install.product_code = value.string()?,
+            INSTALL_SETTINGS => {
+                let mut settings = Reader::new(value.bytes()?);
+                while let Some((field, value)) = settings.field()? {
+                    match field {
+                        SETTINGS_INSTALL_PATH => install.install_path = value.string()?,
+                        SETTINGS_TEXT_LANGUAGE => install.locale = value.string()?,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(install)
+}
+
+// protobuf 필드 값, 쓰지 않는 고정 길이 값은 내용을 버림
+enum Value<'a> {
+    Varint,
+    Fixed,
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Value<'a> {
+    fn bytes(self) -> Result<&'a [u8], String> {
+        match self {
+            Value::Bytes(bytes) => Ok(bytes),
+            _ => Err("product.db 형식을 알 수 없습니다.".to_string()),
+        }
+    }
+
+    fn string(self) -> Result<String, String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|err| err.to_string())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    // 다음 (필드 번호, 값), 끝이면 `None`
+    fn field(&mut self) -> Result<Option<(u64, Value<'a>)>, String> {
+        if self.offset == self.bytes.len() {
+            return Ok(None);
+        }
+
+        let key = self.varint()?;
+        let value = match key & 0x7 {
+            0 => {
+                self.varint()?;
+                Value::Varint
+            }
+            1 => {
+                self.take(8)?;
+                Value::Fixed
+            }
+            2 => {
+                let length = usize::try_from(self.varint()?).map_err(|_| truncated())?;
+                Value::Bytes(self.take(length)?)
+            }
+            5 => {
+                self.take(4)?;
+                Value::Fixed
+            }
+            _ => return Err("product.db 형식을 알 수 없습니다.".to_string()),
+        };
+
+        Ok(Some((key >> 3, value)))
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.bytes.get(self.offset).ok_or_else(truncated)?;
+            self.offset += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("product.db 형식을 알 수 없습니다.".to_string())
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self.offset.checked_add(length).ok_or_else(truncated)?;
+        let bytes = self.bytes.get(self.offset..end).ok_or_else(truncated)?;
+        self.offset = end;
+
+        Ok(bytes)
+    }
+}
+
+fn truncated() -> String {
+    "product.db가 잘렸습니다.".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // StarCraft: Remastered(koKR), StarCraft II, Battle.net 앱과 모르는 필드가 있는 예제
+    const PRODUCT_DB: &[u8] = include_bytes!("../testdata/product.db");
+    // StarCraft II만 설치된 예제
+    const WITHOUT_STARCRAFT: &[u8] = include_bytes!("../testdata/product-without-s1.db");
+
+    #[test]
+    fn finds_starcraft_install() {
+        let install = starcraft(PRODUCT_DB).unwrap().unwrap();
+
+        assert_eq!(
+            install,
+            ProductInstall {
+                uid: "s1".to_string(),
+                product_code: "s1".to_string(),
+                install_path: "C:/Program Files (x86)/StarCraft".to_string(),
+                locale: "koKR".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn reads_every_product() {
+        let codes: Vec<_> = parse(PRODUCT_DB)
+            .unwrap()
+            .into_iter()
+            .map(|install| install.product_code)
+            .collect();
+
+        assert_eq!(codes, ["bna", "s2", "s1"]);
+        assert_eq!(starcraft(WITHOUT_STARCRAFT).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_damaged_database() {
+        let truncated = &PRODUCT_DB[..PRODUCT_DB.len() - 3];
+
+        assert_eq!(parse(truncated).unwrap_err(), "product.db가 잘렸습니다.");
+        assert!(parse(&[0x0b]).is_err());
+        assert_eq!(parse(&[]).unwrap(), []);
+    }
+}
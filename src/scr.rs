@@ -1,99 +1,154 @@
-#![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]
+//! 프로세스 조작 백엔드와 로그 유틸리티
 
-use std::collections::BTreeSet;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
 
 use chrono::Local;
-use windows::{
-    Wdk::{
-        Foundation::{NtQueryObject, OBJECT_INFORMATION_CLASS, OBJECT_NAME_INFORMATION},
-        System::{
-            SystemInformation::{NtQuerySystemInformation, SystemProcessInformation},
-            Threading::{NtQueryInformationProcess, ProcessHandleInformation},
-        },
-    },
-    Win32::{
-        Foundation::{
-            CloseHandle, DUPLICATE_CLOSE_SOURCE, DUPLICATE_SAME_ACCESS, DuplicateHandle,
-            ERROR_ALREADY_EXISTS, GetLastError, HANDLE, STATUS_INFO_LENGTH_MISMATCH,
-            STATUS_SUCCESS, UNICODE_STRING,
-        },
-        System::{
-            Com::{CLSCTX_INPROC_SERVER, CoCreateInstance},
-            Threading::{
-                CREATE_NEW_CONSOLE, CREATE_NO_WINDOW, CreateMutexW, CreateProcessW,
-                GetCurrentProcess, OpenProcess, PROCESS_ALL_ACCESS, PROCESS_INFORMATION,
-                STARTUPINFOW,
-            },
-        },
-        UI::Shell::{Common::COMDLG_FILTERSPEC, FileOpenDialog, IFileDialog, SIGDN_FILESYSPATH},
-    },
-    core::{HSTRING, Owned, PCWSTR, PWSTR, w},
+
+use crate::{
+    Event, InstanceId, LaunchRequest, SCRStruct, State, game::GameDefinition, settings::Settings,
 };
 
-use crate::{APP_NAME, SCRStruct};
-
-#[repr(C)]
-#[derive(Debug)]
-struct SYSTEM_PROCESS_INFORMATION {
-    pub NextEntryOffset: u32,
-    pub NumberOfThreads: u32,
-    pub Reserved1: [u8; 48],
-    pub ImageName: UNICODE_STRING,
-    pub BasePriority: i32,
-    pub UniqueProcessId: HANDLE,
+pub mod cmdline;
+#[cfg(test)]
+mod damage;
+#[cfg(any(windows, test))]
+mod environment;
+pub mod fake;
+#[cfg(any(windows, test))]
+mod handle_snapshot;
+#[cfg(any(windows, test))]
+mod nt_buffer;
+#[cfg(unix)]
+mod prefix;
+#[cfg(any(windows, test))]
+mod process_list;
+#[cfg(any(windows, test))]
+mod timeout;
+#[cfg(unix)]
+mod unix;
+#[cfg(windows)]
+mod windows;
+#[cfg(unix)]
+mod wine;
+
+// 현재 플랫폼에서 사용하는 백엔드
+#[cfg(unix)]
+pub use unix::{attach_console, get_mutex, get_path};
+#[cfg(windows)]
+pub use windows::{WindowsBackend as SystemBackend, attach_console, get_mutex, get_path};
+#[cfg(unix)]
+pub use wine::WineBackend as SystemBackend;
+
+const MUTEX_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 실행한 클라이언트의 뮤텍스가 생길 때까지 기다리는 기본 시간
+pub const MUTEX_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 프로세스 안에서 찾은 이름 있는 핸들
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedHandle {
+    /// 대상 프로세스 안의 핸들 값
+    pub value: usize,
+    /// 객체 이름
+    pub name: String,
 }
 
-#[repr(C)]
-#[derive(Debug)]
-struct PROCESS_HANDLE_SNAPSHOT_INFORMATION {
-    pub NumberOfHandles: usize,
-    pub Reserved: usize,
-    pub Handles: [PROCESS_HANDLE_TABLE_ENTRY_INFO; 0],
+/// 열어 둔 프로세스가 끝났는지
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liveness {
+    /// 실행 중
+    Running,
+    /// 끝남, 종료 코드를 모르면 `None`
+    Exited(Option<u32>),
 }
 
-#[repr(C)]
-#[derive(Debug)]
-struct PROCESS_HANDLE_TABLE_ENTRY_INFO {
-    pub HandleValue: HANDLE,
-    pub HandleCount: usize,
-    pub PointerCount: usize,
-    pub GrantedAccess: u32,
-    pub ObjectTypeIndex: u32,
-    pub HandleAttributes: u32,
-    pub Reserved: u32,
-}
+/// 런처가 닫은 중복 실행 방지 객체, 복제해도 같은 기록을 가리킴
+///
+/// Tick과 일괄 실행이 같은 클라이언트의 뮤텍스를 동시에 닫으려 할 때, 늦은 쪽이 이미 닫혔다는
+/// 것을 알 수 있게 한다.
+#[derive(Debug, Clone, Default)]
+pub struct ClosedLocks(Arc<Mutex<BTreeMap<InstanceId, usize>>>);
+
+impl ClosedLocks {
+    // 인스턴스 `id`에서 닫은 핸들 값
+    fn get(&self, id: InstanceId) -> Option<usize> {
+        self.0.lock().unwrap().get(&id).copied()
+    }
 
-const ObjectNameInformation: OBJECT_INFORMATION_CLASS = OBJECT_INFORMATION_CLASS(1i32);
+    fn insert(&self, id: InstanceId, handle: usize) {
+        self.0.lock().unwrap().insert(id, handle);
+    }
 
-pub fn get_mutex() -> bool {
-    unsafe {
-        CreateMutexW(None, false, PCWSTR(HSTRING::from(APP_NAME).as_ptr())).is_ok()
-            && GetLastError() != ERROR_ALREADY_EXISTS
+    fn remove(&self, id: InstanceId) {
+        self.0.lock().unwrap().remove(&id);
     }
 }
 
-pub fn get_path() -> Option<String> {
-    unsafe {
-        let dialog =
-            CoCreateInstance::<_, IFileDialog>(&FileOpenDialog, None, CLSCTX_INPROC_SERVER).ok()?;
-
-        dialog.SetTitle(w!("스타크래프트 실행 파일 선택")).ok()?;
-        dialog
-            .SetFileTypes(&[COMDLG_FILTERSPEC {
-                pszName: w!("StarCraft.exe"),
-                pszSpec: w!("StarCraft.exe"),
-            }])
-            .ok()?;
-        dialog.Show(None).ok()?;
-        dialog
-            .GetResult()
-            .and_then(|item| item.GetDisplayName(SIGDN_FILESYSPATH))
-            .ok()
-            .and_then(|item| item.to_string().ok().filter(|e| !e.is_empty()))
+/// 런처가 사용하는 프로세스 조작을 추상화한 트레이트
+pub trait ProcessBackend: Clone + Send + Sync + 'static {
+    /// 열린 프로세스, 버리면 닫힘
+    type Handle: Send + 'static;
+
+    /// 설정을 불러오거나 저장할 때 호출됨
+    fn configure(&mut self, _settings: &Settings) {}
+
+    /// 찾고 다루는 게임
+    fn game(&self) -> &GameDefinition;
+
+    /// 시작할 때 읽은 게임 정의로 바꿈
+    fn set_game(&mut self, game: GameDefinition);
+
+    /// 뮤텍스 핸들을 닫아야 다음 클라이언트를 실행할 수 있는지
+    fn closes_mutex(&self) -> bool {
+        true
     }
+
+    /// 뮤텍스를 닫은 기록, 복제한 백엔드끼리 공유해야 함
+    fn closed_locks(&self) -> &ClosedLocks;
+
+    /// 실행 중인 클라이언트 프로세스 목록, 실행 파일 이름은 [`game`](Self::game)으로 정함
+    fn enumerate(&self) -> Vec<SCRStruct>;
+
+    /// `pid` 프로세스의 실행 파일 이름, 알 수 없으면 `None`
+    fn process_name(&self, _pid: u32) -> Option<String> {
+        None
+    }
+
+    /// `pid` 프로세스를 열고, 살아 있지 않으면 `None`
+    fn open(&self, pid: u32) -> Option<Self::Handle>;
+
+    /// 열어 둔 프로세스가 끝났는지
+    fn liveness(&self, process: &Self::Handle) -> Liveness;
+
+    /// 열어 둔 프로세스의 생성 시각, 단위는 [`SCRStruct::created`]와 같음
+    fn creation_time(&self, _process: &Self::Handle) -> Option<u64> {
+        None
+    }
+
+    /// 프로세스가 가진 핸들 중 이름을 얻을 수 있는 핸들 목록
+    fn scan_handles(&self, process: &Self::Handle) -> Vec<NamedHandle>;
+
+    /// 대상 프로세스 안의 핸들을 닫음
+    fn close_handle(&self, process: &Self::Handle, handle: &NamedHandle) -> bool;
+
+    /// 클라이언트를 실행하고 PID와 프로세스를 반환
+    fn spawn(&self, request: &LaunchRequest) -> Option<(u32, Self::Handle)>;
+
+    /// 프로세스를 강제 종료
+    fn terminate(&self, process: &Self::Handle) -> bool;
 }
 
+/// 로그 문자열 변환
 pub trait StringExt {
+    /// 현재 시각을 앞에 붙인 로그 한 줄
     fn as_log(&self) -> String;
 }
 
@@ -103,6 +158,7 @@ impl StringExt for str {
     }
 }
 
+/// 로그를 `YYYY-MM-DD.txt`에 합쳐서 저장
 pub async fn save_log(mut logs: BTreeSet<String>) -> Result<(), String> {
     use tokio::{
         fs::File,
@@ -126,195 +182,128 @@ pub async fn save_log(mut logs: BTreeSet<String>) -> Result<(), String> {
     Ok(())
 }
 
-pub fn get_owned_handle(pid: u32) -> Option<Owned<HANDLE>> {
-    let owned_handle = unsafe { Owned::new(OpenProcess(PROCESS_ALL_ACCESS, false, pid).ok()?) };
+/// `pid` 프로세스에서 중복 실행 방지 뮤텍스를 찾아 닫음
+pub fn query_child<B: ProcessBackend>(
+    backend: &B,
+    pid: u32,
+    maybe_handle: Option<B::Handle>,
+) -> Option<Event> {
+    let process = maybe_handle.or_else(|| backend.open(pid))?;
 
-    Some(owned_handle)
+    close_mutex(backend, pid, &process)
 }
 
-pub fn process_handles() -> Vec<SCRStruct> {
-    let mut process_list_size: u32 = 0;
-    let mut processes = Vec::new();
-    let mut childs = Vec::new();
-    loop {
-        let status = unsafe {
-            NtQuerySystemInformation(
-                SystemProcessInformation,
-                processes.as_mut_ptr() as _,
-                processes.len() as _,
-                &mut process_list_size,
-            )
-        };
-
-        match status {
-            STATUS_INFO_LENGTH_MISMATCH => processes.resize(process_list_size as _, 0u8),
-            STATUS_SUCCESS => {
-                let mut current_offset = 0;
-                loop {
-                    let current = unsafe {
-                        &*(processes.as_ptr().add(current_offset)
-                            as *const SYSTEM_PROCESS_INFORMATION)
-                    };
-                    let handle = current.UniqueProcessId;
-                    if !handle.is_invalid() && current.ImageName.Length > 0 {
-                        if let Ok(name) = unsafe { current.ImageName.Buffer.to_string() } {
-                            if name.eq_ignore_ascii_case("starcraft.exe") {
-                                childs.push(SCRStruct::new(current.UniqueProcessId.0 as u32));
-                            }
-                        }
-                    }
-                    if current.NextEntryOffset == 0 {
-                        break;
-                    }
-                    current_offset += current.NextEntryOffset as usize;
-                }
-                break;
+/// 열어 둔 `pid` 프로세스에서 중복 실행 방지 뮤텍스를 찾아 닫음
+///
+/// 게임 정의의 [`lock_names`](GameDefinition::lock_names) 중 하나가 이름에 들어 있는 첫 핸들을
+/// 닫는다.
+pub fn close_mutex<B: ProcessBackend>(backend: &B, pid: u32, process: &B::Handle) -> Option<Event> {
+    backend
+        .scan_handles(process)
+        .into_iter()
+        .filter(|handle| backend.game().is_lock(&handle.name))
+        .find(|handle| backend.close_handle(process, handle))
+        .map(|handle| {
+            backend
+                .closed_locks()
+                .insert((pid, backend.creation_time(process)), handle.value);
+            Event::MutexClosed {
+                pid,
+                handle: handle.value,
             }
-            _ => break,
-        }
-    }
+        })
+}
 
-    childs
+/// `child`와 같은 프로세스일 때만 열어서 반환
+///
+/// PID가 재사용되어 생성 시각이 다르면 `None`이다.
+pub fn open_instance<B: ProcessBackend>(backend: &B, child: &SCRStruct) -> Option<B::Handle> {
+    let process = backend.open(child.pid)?;
+    match child.created {
+        Some(created) if backend.creation_time(&process) != Some(created) => None,
+        _ => Some(process),
+    }
 }
 
-pub fn query_child(pid: u32, maybe_handle: Option<Owned<HANDLE>>) -> Option<String> {
-    let mut buffer = Vec::new();
-    let mut size = 0;
-    let owned_handle = maybe_handle.or_else(|| get_owned_handle(pid))?;
+/// 추적 중인 인스턴스 `child`를 한 번 확인하고 일어난 일을 순서대로 반환
+///
+/// `running`은 이번 프로세스 목록에 있었는지다. 종료 코드를 읽을 수 있도록 연 프로세스는 끝날
+/// 때까지 [`SCRStruct::id`]별로 `processes`에 둔다. 확인하는 동안에는 핸들을 꺼내 두므로
+/// `processes`는 이 인스턴스의 핸들을 넣고 뺄 때만 잠긴다.
+pub fn poll<B: ProcessBackend>(
+    backend: &B,
+    child: &SCRStruct,
+    running: bool,
+    processes: &Mutex<BTreeMap<InstanceId, B::Handle>>,
+) -> Vec<Event> {
+    let pid = child.pid;
+    let mut events = Vec::new();
+    if running && matches!(child.state(), None | Some(State::Launching)) {
+        events.push(Event::Found { pid });
+    }
+    let opened = processes.lock().unwrap().remove(&child.id());
+    let process = match opened.or_else(|| open_instance(backend, child)) {
+        Some(process) => process,
+        // 목록에 있는데 열 수 없으면 권한 문제
+        None if running => {
+            events.push(Event::OpenFailed { pid });
+            return events;
+        }
+        None => {
+            events.push(Event::Exited { pid, code: None });
+            return events;
+        }
+    };
+    if let Liveness::Exited(code) = backend.liveness(&process) {
+        backend.closed_locks().remove(child.id());
+        events.push(Event::Exited { pid, code });
+        return events;
+    }
+    if child.state() != Some(State::MutexClosed)
+        && let Some(event) = close_mutex(backend, pid, &process)
+    {
+        events.push(event);
+    }
+    processes.lock().unwrap().insert(child.id(), process);
 
-    loop {
-        let status = unsafe {
-            NtQueryInformationProcess(
-                *owned_handle,
-                ProcessHandleInformation,
-                buffer.as_mut_ptr() as *mut _,
-                buffer.len() as u32,
-                &mut size,
-            )
-        };
-
-        match status {
-            STATUS_INFO_LENGTH_MISMATCH => buffer.resize(size as _, 0u8),
-            STATUS_SUCCESS => {
-                let handles_slice = unsafe {
-                    let process_handle_snapshot_information =
-                        &*(buffer.as_ptr() as *const PROCESS_HANDLE_SNAPSHOT_INFORMATION);
-                    let handles_ptr = process_handle_snapshot_information.Handles.as_ptr();
-                    let number_of_handles = process_handle_snapshot_information.NumberOfHandles;
-
-                    std::slice::from_raw_parts(handles_ptr, number_of_handles)
-                };
-
-                for handle_info in handles_slice {
-                    let mut new_handle = HANDLE::default();
-                    if unsafe {
-                        DuplicateHandle(
-                            *owned_handle,
-                            handle_info.HandleValue,
-                            GetCurrentProcess(),
-                            &mut new_handle,
-                            0,
-                            false,
-                            DUPLICATE_SAME_ACCESS,
-                        )
-                    }
-                    .is_err()
-                    {
-                        continue;
-                    }
-
-                    let mut object_buf: Vec<u8> = Vec::new();
-                    let mut object_len: u32 = 0;
-
-                    loop {
-                        let status = unsafe {
-                            NtQueryObject(
-                                Some(new_handle),
-                                ObjectNameInformation,
-                                Some(object_buf.as_mut_ptr() as *mut _),
-                                object_buf.len() as u32,
-                                Some(&mut object_len),
-                            )
-                        };
-
-                        match status {
-                            STATUS_INFO_LENGTH_MISMATCH => {
-                                object_buf.resize(object_len as usize, 0u8)
-                            }
-                            STATUS_SUCCESS => {
-                                if unsafe { CloseHandle(new_handle) }.is_err() {
-                                    break;
-                                };
-
-                                let p_object_info = unsafe {
-                                    &*(object_buf.as_ptr() as *const OBJECT_NAME_INFORMATION)
-                                };
-
-                                if p_object_info.Name.Length > 0 {
-                                    let name = unsafe { p_object_info.Name.Buffer.to_string() };
-                                    if name.is_ok_and(|name| {
-                                        name.contains("Starcraft Check For Other Instances")
-                                    }) && unsafe {
-                                        DuplicateHandle(
-                                            *owned_handle,
-                                            handle_info.HandleValue,
-                                            GetCurrentProcess(),
-                                            &mut new_handle,
-                                            0,
-                                            false,
-                                            DUPLICATE_CLOSE_SOURCE, // Close Source HANDLE
-                                        )
-                                        .and_then(|_| CloseHandle(new_handle))
-                                    }
-                                    .is_ok()
-                                    {
-                                        return Some(
-                                            format!(
-                                                "Closed {:?} for StarCraft.exe (PID: {})",
-                                                handle_info.HandleValue, pid
-                                            )
-                                            .as_log(),
-                                        );
-                                    }
-                                }
-                                break;
-                            }
-                            _ => break,
-                        } // match
-                    } // loop
-                } // for
-                break;
-            }
-            _ => break,
-        } // match
-    } // loop
+    events
+}
 
-    None
+/// 실행한 클라이언트의 뮤텍스를 닫을 때까지 기다림
+pub fn wait_mutex<B: ProcessBackend>(backend: &B, pid: u32, handle: B::Handle) -> Option<Event> {
+    wait_mutex_until(backend, pid, handle, MUTEX_TIMEOUT, &AtomicBool::new(false))
 }
 
-pub fn run_scr(path: &str, args: &[&str]) -> Option<(u32, Owned<HANDLE>)> {
-    let mut cmd = vec![path];
-    cmd.extend_from_slice(args);
-    let mut process_info = PROCESS_INFORMATION::default();
-    let startup_info = STARTUPINFOW::default();
-
-    let owned_handle = unsafe {
-        CreateProcessW(
-            None,
-            Some(PWSTR(HSTRING::from(cmd.join(" ")).as_ptr() as *mut _)),
-            None,
-            None,
-            false,
-            CREATE_NO_WINDOW | CREATE_NEW_CONSOLE,
-            None,
-            None,
-            &startup_info,
-            &mut process_info,
-        )
-        .ok()?;
-        CloseHandle(process_info.hThread).ok()?;
-        Owned::new(process_info.hProcess)
-    };
+// `timeout`이 지나거나 `cancel`이 설정되면 포기
+pub(crate) fn wait_mutex_until<B: ProcessBackend>(
+    backend: &B,
+    pid: u32,
+    handle: B::Handle,
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> Option<Event> {
+    if !backend.closes_mutex() {
+        return None;
+    }
 
-    Some((process_info.dwProcessId, owned_handle))
+    // PID가 재사용되어도 실행한 프로세스만 보도록 처음 핸들을 계속 씀
+    let id = (pid, backend.creation_time(&handle));
+    let mut waited = Duration::ZERO;
+    loop {
+        // 그사이 Tick이 먼저 닫았을 수 있음
+        if let Some(handle) = backend.closed_locks().get(id) {
+            return Some(Event::MutexClosed { pid, handle });
+        }
+        if let Some(event) = close_mutex(backend, pid, &handle) {
+            return Some(event);
+        }
+        if waited >= timeout
+            || cancel.load(Ordering::Relaxed)
+            || backend.liveness(&handle) != Liveness::Running
+        {
+            return None;
+        }
+        thread::sleep(MUTEX_POLL_INTERVAL);
+        waited += MUTEX_POLL_INTERVAL;
+    }
 }
@@ -0,0 +1,236 @@
+//! `CreateProcessW`에 넘길 명령줄 만들기와 나누기
+//!
+//! 자식 프로세스는 MSVCRT 규칙으로 명령줄을 다시 `argv`로 나누므로, 그 규칙에 맞게 따옴표와
+//! 역슬래시를 붙인다. 설정 창의 인자 입력도 같은 규칙으로 나누므로 모든 OS에서 쓴다.
+
+/// `program`과 `args`를 명령줄 하나로 합침
+///
+/// 실행 파일 경로에 `"`가 있거나 어디든 NUL 문자가 있으면 표현할 수 없으므로 `None`.
+pub fn command_line(program: &str, args: &[String]) -> Option<String> {
+    if program.contains('"') || program.contains('\0') || args.iter().any(|arg| arg.contains('\0'))
+    {
+        return None;
+    }
+
+    // argv[0]은 역슬래시를 특수하게 다루지 않고 따옴표만 본다
+    let mut line = String::with_capacity(program.len() + 2);
+    if program.is_empty() || program.contains([' ', '\t']) {
+        line.push('"');
+        line.push_str(program);
+        line.push('"');
+    } else {
+        line.push_str(program);
+    }
+
+    for arg in args {
+        line.push(' ');
+        push_arg(&mut line, arg);
+    }
+
+    Some(line)
+}
+
+fn push_arg(line: &mut String, arg: &str) {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\x0b', '"']) {
+        line.push_str(arg);
+        return;
+    }
+
+    // `--name=value`는 값만 감싸 Battle.net이 문서에 쓰는 `--exec="launch S1"` 모양을 만듦
+    let value = match arg.split_once('=') {
+        Some((name, value))
+            if name.starts_with('-') && !name.contains([' ', '\t', '\n', '\x0b', '"']) =>
+        {
+            line.push_str(name);
+            line.push('=');
+            value
+        }
+        _ => arg,
+    };
+
+    line.push('"');
+    let mut backslashes = 0;
+    for c in value.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // 따옴표 앞의 역슬래시는 두 배로, 따옴표는 \"
+                line.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                line.extend(std::iter::repeat_n('\\', backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            line.push(c);
+        }
+    }
+    // 닫는 따옴표 앞의 역슬래시도 두 배로
+    line.extend(std::iter::repeat_n('\\', backslashes * 2));
+    line.push('"');
+}
+
+/// MSVCRT(2008 이후)가 `argv[1..]`을 나누는 방법으로 `line`을 나눔
+pub fn split_args(line: &str) -> Vec<String> {
+    let mut chars = line.chars().peekable();
+    let mut args = Vec::new();
+
+    loop {
+        while chars.next_if(|c| matches!(c, ' ' | '\t')).is_some() {}
+        if chars.peek().is_none() {
+            return args;
+        }
+
+        let mut arg = String::new();
+        let mut quoted = false;
+        while let Some(&c) = chars.peek() {
+            if !quoted && matches!(c, ' ' | '\t') {
+                break;
+            }
+            chars.next();
+            match c {
+                '\\' => {
+                    let mut backslashes = 1;
+                    while chars.next_if_eq(&'\\').is_some() {
+                        backslashes += 1;
+                    }
+                    if chars.peek() == Some(&'"') {
+                        arg.extend(std::iter::repeat_n('\\', backslashes / 2));
+                        if backslashes % 2 == 1 {
+                            chars.next();
+                            arg.push('"');
+                        }
+                    } else {
+                        arg.extend(std::iter::repeat_n('\\', backslashes));
+                    }
+                }
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    arg.push('"');
+                }
+                '"' => quoted = !quoted,
+                _ => arg.push(c),
+            }
+        }
+        args.push(arg);
+    }
+}
+
+/// `args`를 [`split_args`]로 다시 나눌 수 있게 합침
+pub fn join_args(args: &[String]) -> String {
+    let mut line = String::new();
+    for (index, arg) in args.iter().enumerate() {
+        if index > 0 {
+            line.push(' ');
+        }
+        push_arg(&mut line, arg);
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle_net;
+
+    fn line(program: &str, args: &[&str]) -> Option<String> {
+        let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+        command_line(program, &args)
+    }
+
+    #[test]
+    fn quotes_program_path() {
+        let cases = [
+            ("StarCraft.exe", "StarCraft.exe"),
+            (
+                "C:\\Program Files (x86)\\StarCraft\\x86_64\\StarCraft.exe",
+                "\"C:\\Program Files (x86)\\StarCraft\\x86_64\\StarCraft.exe\"",
+            ),
+            // argv[0]의 끝 역슬래시는 두 배로 하지 않음
+            ("C:\\Games Dir\\", "\"C:\\Games Dir\\\""),
+            ("", "\"\""),
+        ];
+
+        for (program, expected) in cases {
+            assert_eq!(line(program, &[]).unwrap(), expected, "{program}");
+        }
+    }
+
+    #[test]
+    fn quotes_arguments() {
+        let cases = [
+            ("-launch", "-launch"),
+            ("", "\"\""),
+            ("two words", "\"two words\""),
+            ("tab\there", "\"tab\there\""),
+            ("say \"hi\"", "\"say \\\"hi\\\"\""),
+            ("\"", "\"\\\"\""),
+            ("C:\\path\\", "C:\\path\\"),
+            ("C:\\my path\\", "\"C:\\my path\\\\\""),
+            ("a\\\\b c", "\"a\\\\b c\""),
+            ("a\\\"b", "\"a\\\\\\\"b\""),
+            ("\\\\server\\share", "\\\\server\\share"),
+            ("한글 인자", "\"한글 인자\""),
+            ("--exec=launch S1", "--exec=\"launch S1\""),
+            ("--name=", "--name="),
+            ("--name=a \"b\"", "--name=\"a \\\"b\\\"\""),
+            ("-x y=z", "\"-x y=z\""),
+        ];
+
+        for (arg, expected) in cases {
+            assert_eq!(
+                line("StarCraft.exe", &[arg]).unwrap(),
+                format!("StarCraft.exe {expected}"),
+                "{arg}"
+            );
+        }
+    }
+
+    #[test]
+    fn arguments_survive_msvcrt_parsing() {
+        let args = [
+            "-launch",
+            "",
+            "two words",
+            "trailing\\",
+            "trailing space\\",
+            "\\\"",
+            "a\\\\\"b\\\\",
+            "\"\"",
+            " leading",
+            "new\nline",
+            "{profile}-{slot}",
+            "--exec=launch S1",
+            "--dir=C:\\my path\\",
+        ];
+
+        let line = line("C:\\Program Files (x86)\\StarCraft.exe", &args).unwrap();
+        let rest = line
+            .strip_prefix("\"C:\\Program Files (x86)\\StarCraft.exe\"")
+            .unwrap();
+
+        assert_eq!(split_args(rest), args);
+        assert_eq!(split_args(&join_args(&args.map(str::to_string))), args);
+    }
+
+    #[test]
+    fn battle_net_argument_keeps_documented_form() {
+        assert_eq!(
+            command_line(
+                battle_net::DEFAULT_PATH,
+                &[battle_net::LAUNCH_ARG.to_string()]
+            )
+            .unwrap(),
+            "\"C:\\Program Files (x86)\\Battle.net\\Battle.net.exe\" --exec=\"launch S1\""
+        );
+    }
+
+    #[test]
+    fn rejects_unrepresentable() {
+        assert_eq!(line("C:\\Star\"Craft.exe", &[]), None);
+        assert_eq!(line("StarCraft.exe", &["nul\0"]), None);
+    }
+}
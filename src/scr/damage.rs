@@ -0,0 +1,55 @@
+//! 파서 테스트에 쓰는 손상된 버퍼
+//!
+//! NT API가 채운 버퍼를 읽는 파서는 어떤 입력에도 패닉하지 않아야 하므로, 정상 버퍼를 자르거나
+//! 바이트를 바꾼 버퍼를 모두 넣어 본다. 난수는 고정된 시드에서 시작하므로 실패가 재현된다.
+
+// 바이트마다 넣어 보는 값, 길이와 오프셋 필드가 넘치거나 음수가 되는 경계
+const BOUNDARY_BYTES: [u8; 4] = [0x00, 0x7f, 0x80, 0xff];
+const RANDOM_CASES: usize = 2000;
+const NOISE_CASES: usize = 500;
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+// xorshift 난수
+fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// `buffer`에서 만든 손상된 버퍼
+///
+/// 모든 길이로 자른 버퍼, 바이트 하나를 경계 값으로 바꾼 버퍼, 여러 바이트를 무작위로 바꾸고
+/// 무작위 길이로 자른 버퍼를 차례로 만든다. `buffer`는 비어 있지 않아야 한다.
+pub fn damaged(buffer: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    let truncated = (0..buffer.len()).map(|length| buffer[..length].to_vec());
+    let boundary = (0..buffer.len()).flat_map(move |index| {
+        BOUNDARY_BYTES.map(|value| {
+            let mut damaged = buffer.to_vec();
+            damaged[index] = value;
+            damaged
+        })
+    });
+    let mut state = SEED;
+    let random = (0..RANDOM_CASES).map(move |_| {
+        let mut damaged = buffer.to_vec();
+        for _ in 0..1 + next(&mut state) % 8 {
+            let index = next(&mut state) as usize % damaged.len();
+            damaged[index] = next(&mut state) as u8;
+        }
+        damaged.truncate(next(&mut state) as usize % (damaged.len() + 1));
+        damaged
+    });
+
+    truncated.chain(boundary).chain(random)
+}
+
+/// 1024바이트 미만의 무작위 바이트
+pub fn noise() -> impl Iterator<Item = Vec<u8>> {
+    let mut state = SEED;
+
+    (0..NOISE_CASES).map(move |_| {
+        let length = next(&mut state) as usize % 1024;
+        (0..length).map(|_| next(&mut state) as u8).collect()
+    })
+}
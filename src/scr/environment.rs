@@ -0,0 +1,89 @@
+//! `CreateProcessW`에 넘길 환경 블록 만들기
+//!
+//! 명령줄과 마찬가지로 플랫폼과 무관한 문자열 처리라서 모든 OS에서 테스트한다.
+
+use std::collections::BTreeMap;
+
+/// 런처의 환경 변수 `base`에 `overrides`를 더하거나 덮어씀
+///
+/// Windows처럼 이름의 대소문자를 구분하지 않으며, 환경 블록 규칙에 맞게 대소문자 구분 없이
+/// 이름순으로 정렬한다.
+pub fn merge_env(
+    base: impl IntoIterator<Item = (String, String)>,
+    overrides: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut merged = BTreeMap::new();
+    let overrides = overrides
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()));
+    for (name, value) in base.into_iter().chain(overrides) {
+        merged.insert(name.to_uppercase(), (name, value));
+    }
+
+    merged.into_values().collect()
+}
+
+/// `NAME=value\0`를 이어 붙이고 `\0`으로 끝나는 UTF-16 환경 블록
+pub fn environment_block(vars: &[(String, String)]) -> Vec<u16> {
+    let mut block: Vec<u16> = vars
+        .iter()
+        .flat_map(|(name, value)| {
+            format!("{name}={value}\0")
+                .encode_utf16()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    // 변수가 하나도 없어도 블록은 NUL 두 개로 끝나야 함
+    if block.is_empty() {
+        block.push(0);
+    }
+    block.push(0);
+
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn overrides_replace_regardless_of_case() {
+        let base = vars(&[
+            ("Path", "C:\\Windows"),
+            ("TEMP", "C:\\Temp"),
+            ("lang", "en"),
+        ]);
+        let overrides = BTreeMap::from([
+            ("PATH".to_string(), "C:\\StarCraft".to_string()),
+            ("SCR_SLOT".to_string(), "1".to_string()),
+        ]);
+
+        let merged = merge_env(base, &overrides);
+
+        assert_eq!(
+            merged,
+            vars(&[
+                ("lang", "en"),
+                ("PATH", "C:\\StarCraft"),
+                ("SCR_SLOT", "1"),
+                ("TEMP", "C:\\Temp"),
+            ])
+        );
+    }
+
+    #[test]
+    fn block_is_double_nul_terminated() {
+        let block = environment_block(&vars(&[("A", "1"), ("언어", "한국어")]));
+        let text = String::from_utf16(&block).unwrap();
+
+        assert_eq!(text, "A=1\0언어=한국어\0\0");
+        assert_eq!(environment_block(&[]), [0, 0]);
+    }
+}
@@ -0,0 +1,313 @@
+//! 테스트용 가짜 백엔드
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use crate::{
+    LaunchRequest, SCRStruct,
+    game::{GameDefinition, MUTEX_NAME},
+    scr::{ClosedLocks, Liveness, NamedHandle, ProcessBackend},
+};
+
+const FIRST_PID: u32 = 1000;
+const IMAGE_NAME: &str = "StarCraft.exe";
+
+/// 메모리 안에서 프로세스를 흉내 내는 백엔드, 복제본끼리 상태를 공유
+///
+/// 연 핸들의 수를 세므로 [`FakeBackend::open_handles`]로 핸들이 새는지 확인할 수 있다.
+#[derive(Debug, Clone, Default)]
+pub struct FakeBackend {
+    state: Arc<Mutex<FakeState>>,
+    game: GameDefinition,
+    closed_locks: ClosedLocks,
+    // 아직 버리지 않은 FakeHandle 수, 핸들을 버릴 때는 상태를 잠그지 않도록 따로 둠
+    open_handles: Arc<AtomicUsize>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    next_pid: u32,
+    // 프로세스 생성 시각으로 쓰는 카운터
+    clock: u64,
+    processes: BTreeMap<u32, FakeProcess>,
+    spawned: Vec<LaunchRequest>,
+    fail_spawn: bool,
+    spawn_without_mutex: bool,
+}
+
+#[derive(Debug)]
+struct FakeProcess {
+    alive: bool,
+    exit_code: Option<u32>,
+    created: u64,
+    name: String,
+    parent: Option<u32>,
+    handles: Vec<NamedHandle>,
+}
+
+impl FakeProcess {
+    fn without_mutex(name: &str) -> Self {
+        Self {
+            alive: true,
+            exit_code: None,
+            created: 0,
+            name: name.to_string(),
+            parent: None,
+            handles: Vec::new(),
+        }
+    }
+
+    fn with_mutex(name: &str) -> Self {
+        Self {
+            alive: true,
+            exit_code: None,
+            created: 0,
+            name: name.to_string(),
+            parent: None,
+            handles: vec![
+                NamedHandle {
+                    value: 0x10,
+                    name: "\\Device\\HarddiskVolume3".to_string(),
+                },
+                NamedHandle {
+                    value: 0x2c,
+                    name: format!("\\Sessions\\1\\BaseNamedObjects\\{MUTEX_NAME}"),
+                },
+            ],
+        }
+    }
+}
+
+impl FakeState {
+    // 생성 시각을 붙여 프로세스를 추가, 같은 PID의 이전 프로세스는 사라짐
+    fn insert(&mut self, pid: u32, mut process: FakeProcess) {
+        self.clock += 1;
+        process.created = self.clock;
+        self.processes.insert(pid, process);
+    }
+
+    // 핸들이 가리키는 프로세스, PID가 재사용되었으면 `None`
+    fn process(&self, handle: &FakeHandle) -> Option<&FakeProcess> {
+        self.processes
+            .get(&handle.pid)
+            .filter(|p| p.created == handle.created)
+    }
+
+    fn process_mut(&mut self, handle: &FakeHandle) -> Option<&mut FakeProcess> {
+        self.processes
+            .get_mut(&handle.pid)
+            .filter(|p| p.created == handle.created)
+    }
+}
+
+/// [`FakeBackend`]가 연 프로세스, 버리면 닫힘
+#[derive(Debug)]
+pub struct FakeHandle {
+    pid: u32,
+    created: u64,
+    open_handles: Arc<AtomicUsize>,
+}
+
+impl Drop for FakeHandle {
+    fn drop(&mut self) {
+        self.open_handles.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl FakeBackend {
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap()
+    }
+
+    fn handle(&self, pid: u32, created: u64) -> FakeHandle {
+        self.open_handles.fetch_add(1, Ordering::SeqCst);
+        FakeHandle {
+            pid,
+            created,
+            open_handles: Arc::clone(&self.open_handles),
+        }
+    }
+
+    /// 열었지만 아직 닫지 않은 핸들 수
+    pub fn open_handles(&self) -> usize {
+        self.open_handles.load(Ordering::SeqCst)
+    }
+
+    /// 외부에서 실행된 StarCraft.exe 프로세스를 추가
+    pub fn add_process(&self, pid: u32) {
+        self.state()
+            .insert(pid, FakeProcess::with_mutex(IMAGE_NAME));
+    }
+
+    /// `pid` 프로세스가 끝나고 같은 PID로 다른 StarCraft.exe 프로세스가 생긴 것처럼 만듦
+    pub fn reuse_pid(&self, pid: u32) {
+        self.add_process(pid);
+    }
+
+    /// `parent` 프로세스가 실행한 StarCraft.exe 프로세스를 추가
+    pub fn add_child(&self, pid: u32, parent: u32) {
+        let process = FakeProcess {
+            parent: Some(parent),
+            ..FakeProcess::with_mutex(IMAGE_NAME)
+        };
+        self.state().insert(pid, process);
+    }
+
+    /// 프로세스가 스스로 종료된 것처럼 만듦
+    pub fn exit(&self, pid: u32) {
+        if let Some(process) = self.state().processes.get_mut(&pid) {
+            process.alive = false;
+        }
+    }
+
+    /// 프로세스가 `code`로 끝난 것처럼 만듦
+    pub fn exit_with(&self, pid: u32, code: u32) {
+        if let Some(process) = self.state().processes.get_mut(&pid) {
+            process.alive = false;
+            process.exit_code = Some(code);
+        }
+    }
+
+    /// 이후 `spawn`이 실패하게 만듦
+    pub fn fail_spawn(&self, fail: bool) {
+        self.state().fail_spawn = fail;
+    }
+
+    /// 이후 실행되는 프로세스가 뮤텍스를 만들지 않게 함
+    pub fn spawn_without_mutex(&self, without: bool) {
+        self.state().spawn_without_mutex = without;
+    }
+
+    /// 프로세스가 살아 있는지
+    pub fn is_alive(&self, pid: u32) -> bool {
+        self.state().processes.get(&pid).is_some_and(|p| p.alive)
+    }
+
+    /// 프로세스가 아직 중복 실행 방지 뮤텍스를 가지고 있는지
+    pub fn has_mutex(&self, pid: u32) -> bool {
+        self.state()
+            .processes
+            .get(&pid)
+            .is_some_and(|p| p.handles.iter().any(|h| h.name.contains(MUTEX_NAME)))
+    }
+
+    /// 지금까지 받은 실행 요청
+    pub fn spawned(&self) -> Vec<LaunchRequest> {
+        self.state().spawned.clone()
+    }
+}
+
+impl ProcessBackend for FakeBackend {
+    type Handle = FakeHandle;
+
+    fn game(&self) -> &GameDefinition {
+        &self.game
+    }
+
+    fn set_game(&mut self, game: GameDefinition) {
+        self.game = game;
+    }
+
+    fn closed_locks(&self) -> &ClosedLocks {
+        &self.closed_locks
+    }
+
+    fn enumerate(&self) -> Vec<SCRStruct> {
+        self.state()
+            .processes
+            .iter()
+            .filter(|(_, process)| process.alive && self.game.is_client(&process.name))
+            .map(|(pid, process)| SCRStruct {
+                parent_pid: process.parent,
+                created: Some(process.created),
+                ..SCRStruct::new(*pid)
+            })
+            .collect()
+    }
+
+    fn process_name(&self, pid: u32) -> Option<String> {
+        self.state()
+            .processes
+            .get(&pid)
+            .map(|process| process.name.clone())
+    }
+
+    fn open(&self, pid: u32) -> Option<Self::Handle> {
+        let created = self
+            .state()
+            .processes
+            .get(&pid)
+            .filter(|p| p.alive)
+            .map(|p| p.created)?;
+
+        Some(self.handle(pid, created))
+    }
+
+    fn liveness(&self, process: &Self::Handle) -> Liveness {
+        match self.state().process(process) {
+            Some(p) if p.alive => Liveness::Running,
+            Some(p) => Liveness::Exited(p.exit_code),
+            None => Liveness::Exited(None),
+        }
+    }
+
+    fn creation_time(&self, process: &Self::Handle) -> Option<u64> {
+        Some(process.created)
+    }
+
+    fn scan_handles(&self, process: &Self::Handle) -> Vec<NamedHandle> {
+        self.state()
+            .process(process)
+            .filter(|p| p.alive)
+            .map(|p| p.handles.clone())
+            .unwrap_or_default()
+    }
+
+    fn close_handle(&self, process: &Self::Handle, handle: &NamedHandle) -> bool {
+        let mut state = self.state();
+        let Some(p) = state.process_mut(process) else {
+            return false;
+        };
+        let before = p.handles.len();
+        p.handles.retain(|h| h != handle);
+
+        p.handles.len() != before
+    }
+
+    fn spawn(&self, request: &LaunchRequest) -> Option<(u32, Self::Handle)> {
+        let mut state = self.state();
+        if state.fail_spawn {
+            return None;
+        }
+        let pid = FIRST_PID.max(state.next_pid);
+        state.next_pid = pid + 1;
+        // 실행 파일 이름은 경로의 마지막 부분
+        let name = request.path.rsplit(['\\', '/']).next().unwrap_or_default();
+        let process = if state.spawn_without_mutex {
+            FakeProcess::without_mutex(name)
+        } else {
+            FakeProcess::with_mutex(name)
+        };
+        state.insert(pid, process);
+        state.spawned.push(request.clone());
+        let created = state.clock;
+        drop(state);
+
+        Some((pid, self.handle(pid, created)))
+    }
+
+    fn terminate(&self, process: &Self::Handle) -> bool {
+        match self.state().process_mut(process) {
+            Some(p) if p.alive => {
+                p.alive = false;
+                true
+            }
+            _ => false,
+        }
+    }
+}
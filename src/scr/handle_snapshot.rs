@@ -0,0 +1,329 @@
+//! `NtQueryInformationProcess(ProcessHandleInformation)`와 `NtQueryObject` 버퍼 읽기
+//!
+//! 핸들 목록은 `PROCESS_HANDLE_SNAPSHOT_INFORMATION` 머리 뒤에 `NumberOfHandles`개의
+//! `PROCESS_HANDLE_TABLE_ENTRY_INFO`가 이어진다. 개수를 버퍼 크기와 비교한 뒤에만 항목을 읽는다.
+//! 객체 종류 목록(`ObjectTypesInformation`)도 같은 방식으로 읽어, 이름을 묻기 전에 핸들을
+//! 종류 번호로 거를 수 있게 한다. 플랫폼과 무관한 처리라서 모든 OS에서 테스트한다.
+
+use std::slice::ChunksExact;
+
+use crate::{
+    game::GameDefinition,
+    scr::nt_buffer::{POINTER_SIZE, read_u16, read_u32, read_usize, unicode_string},
+};
+
+// PROCESS_HANDLE_SNAPSHOT_INFORMATION의 NumberOfHandles, Reserved 뒤에 항목이 옴
+const NUMBER_OF_HANDLES: usize = 0;
+const HEADER_SIZE: usize = 2 * POINTER_SIZE;
+// PROCESS_HANDLE_TABLE_ENTRY_INFO 필드 위치
+const HANDLE_VALUE: usize = 0;
+const GRANTED_ACCESS: usize = 3 * POINTER_SIZE;
+const OBJECT_TYPE_INDEX: usize = GRANTED_ACCESS + 4;
+const HANDLE_ATTRIBUTES: usize = OBJECT_TYPE_INDEX + 4;
+const ENTRY_SIZE: usize = HANDLE_ATTRIBUTES + 8;
+// OBJECT_TYPES_INFORMATION의 NumberOfTypes 뒤, 포인터 크기에 맞춘 위치부터 종류가 이어짐
+const NUMBER_OF_TYPES: usize = 0;
+const FIRST_TYPE: usize = POINTER_SIZE;
+// OBJECT_TYPE_INFORMATION 필드 위치, 이름은 구조체 바로 뒤에 옴
+const TYPE_NAME: usize = 0;
+const TYPE_INDEX: usize = 2 * POINTER_SIZE + 74;
+const TYPE_SIZE: usize = 2 * POINTER_SIZE + 88;
+
+/// 프로세스의 핸들 하나
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandleEntry {
+    /// 대상 프로세스 안의 핸들 값
+    pub value: usize,
+    /// 핸들에 허용된 권한
+    pub granted_access: u32,
+    /// 객체 종류 번호, 같은 부팅 안에서는 종류마다 같음
+    pub object_type_index: u32,
+    /// `OBJ_INHERIT` 등 핸들 속성
+    pub attributes: u32,
+}
+
+/// [`handles`]가 돌려주는 핸들 목록
+#[derive(Debug, Clone)]
+pub struct Handles<'a>(ChunksExact<'a, u8>);
+
+impl Iterator for Handles<'_> {
+    type Item = HandleEntry;
+
+    fn next(&mut self) -> Option<HandleEntry> {
+        self.0.next().map(|entry| HandleEntry {
+            value: read_usize(entry, HANDLE_VALUE),
+            granted_access: read_u32(entry, GRANTED_ACCESS),
+            object_type_index: read_u32(entry, OBJECT_TYPE_INDEX),
+            attributes: read_u32(entry, HANDLE_ATTRIBUTES),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Handles<'_> {}
+
+/// 핸들 목록 `buffer`의 모든 핸들
+///
+/// `NumberOfHandles`개의 항목이 버퍼에 다 들어 있지 않으면 오류다.
+pub fn handles(buffer: &[u8]) -> Result<Handles<'_>, String> {
+    let header = buffer.get(..HEADER_SIZE).ok_or_else(truncated)?;
+    let size = read_usize(header, NUMBER_OF_HANDLES)
+        .checked_mul(ENTRY_SIZE)
+        .ok_or_else(truncated)?;
+    let entries = buffer
+        .get(HEADER_SIZE..)
+        .and_then(|rest| rest.get(..size))
+        .ok_or_else(truncated)?;
+
+    Ok(Handles(entries.chunks_exact(ENTRY_SIZE)))
+}
+
+/// `NtQueryObject(ObjectNameInformation)` 결과 `buffer`의 객체 이름
+///
+/// `base`는 `buffer`의 주소다. 이름이 없는 객체는 빈 문자열이다.
+pub fn object_name(buffer: &[u8], base: usize) -> Result<String, String> {
+    unicode_string(buffer, base, 0).ok_or_else(|| "객체 이름을 읽을 수 없습니다.".to_string())
+}
+
+/// 커널 객체 종류 하나
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectType {
+    /// [`HandleEntry::object_type_index`]와 비교하는 번호
+    pub index: u32,
+    /// 종류 이름 (`Mutant`, `Event`, `File` 등)
+    pub name: String,
+}
+
+/// `types` 중 `game`이 이름을 확인할 객체 종류의 번호
+pub fn lock_type_indexes(types: &[ObjectType], game: &GameDefinition) -> Vec<u32> {
+    types
+        .iter()
+        .filter(|object_type| game.is_lock_type(&object_type.name))
+        .map(|object_type| object_type.index)
+        .collect()
+}
+
+/// `NtQueryObject(ObjectTypesInformation)` 결과 `buffer`의 모든 객체 종류
+///
+/// `base`는 `buffer`의 주소다.
+pub fn object_types(buffer: &[u8], base: usize) -> Result<Vec<ObjectType>, String> {
+    let count = read_u32(
+        buffer.get(..FIRST_TYPE).ok_or_else(types_truncated)?,
+        NUMBER_OF_TYPES,
+    );
+    let mut types = Vec::new();
+    let mut offset = FIRST_TYPE;
+    for position in 0..count {
+        let entry = buffer
+            .get(offset..)
+            .and_then(|rest| rest.get(..TYPE_SIZE))
+            .ok_or_else(types_truncated)?;
+        let name = unicode_string(buffer, base, offset + TYPE_NAME)
+            .ok_or_else(|| "객체 종류 이름을 읽을 수 없습니다.".to_string())?;
+        // Windows 8.1 전에는 TypeIndex가 없고 종류 번호가 2부터 차례로 매겨짐
+        let index = match entry[TYPE_INDEX] {
+            0 => position + 2,
+            index => u32::from(index),
+        };
+        types.push(ObjectType { index, name });
+
+        let maximum_length = usize::from(read_u16(entry, TYPE_NAME + 2));
+        offset += TYPE_SIZE + maximum_length.next_multiple_of(POINTER_SIZE);
+    }
+
+    Ok(types)
+}
+
+fn types_truncated() -> String {
+    "객체 종류 목록이 잘렸습니다.".to_string()
+}
+
+fn truncated() -> String {
+    "핸들 목록이 잘렸습니다.".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scr::damage;
+
+    const BASE: usize = 0x20_0000;
+
+    // (핸들 값, 종류 번호) 목록으로 만든 핸들 목록
+    fn snapshot(entries: &[(usize, u32)]) -> Vec<u8> {
+        let mut buffer = entries.len().to_le_bytes().to_vec();
+        buffer.resize(HEADER_SIZE, 0);
+        for (value, type_index) in entries {
+            let mut entry = vec![0u8; ENTRY_SIZE];
+            entry[HANDLE_VALUE..HANDLE_VALUE + POINTER_SIZE].copy_from_slice(&value.to_le_bytes());
+            entry[GRANTED_ACCESS..GRANTED_ACCESS + 4].copy_from_slice(&0x1f_0001u32.to_le_bytes());
+            entry[OBJECT_TYPE_INDEX..OBJECT_TYPE_INDEX + 4]
+                .copy_from_slice(&type_index.to_le_bytes());
+            buffer.extend(entry);
+        }
+
+        buffer
+    }
+
+    // UNICODE_STRING 바로 뒤에 이름이 오는 NtQueryObject 결과
+    fn name_information(name: &str) -> Vec<u8> {
+        let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut buffer = (name.len() as u16).to_le_bytes().to_vec();
+        buffer.extend((name.len() as u16 + 2).to_le_bytes());
+        buffer.resize(POINTER_SIZE, 0);
+        buffer.extend((BASE + 2 * POINTER_SIZE).to_le_bytes());
+        buffer.extend(name);
+        // NUL 뒤에 쓰레기 값이 남아 있어도 Length만큼만 읽어야 함
+        buffer.extend([0, 0, b'x', 0]);
+
+        buffer
+    }
+
+    // (이름, TypeIndex) 목록으로 만든 ObjectTypesInformation 결과
+    fn type_list(types: &[(&str, u8)]) -> Vec<u8> {
+        let mut buffer = (types.len() as u32).to_le_bytes().to_vec();
+        buffer.resize(FIRST_TYPE, 0);
+        for (name, index) in types {
+            let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            let maximum_length = name.len() + 2;
+            let offset = buffer.len();
+            let mut entry = vec![0u8; TYPE_SIZE];
+            entry[..2].copy_from_slice(&(name.len() as u16).to_le_bytes());
+            entry[2..4].copy_from_slice(&(maximum_length as u16).to_le_bytes());
+            entry[POINTER_SIZE..2 * POINTER_SIZE]
+                .copy_from_slice(&(BASE + offset + TYPE_SIZE).to_le_bytes());
+            entry[TYPE_INDEX] = *index;
+            buffer.extend(entry);
+            buffer.extend(name);
+            buffer.resize(
+                offset + TYPE_SIZE + maximum_length.next_multiple_of(POINTER_SIZE),
+                0,
+            );
+        }
+
+        buffer
+    }
+
+    #[test]
+    fn iterates_handle_entries() {
+        let buffer = snapshot(&[(0x4, 37), (0x1a8, 17)]);
+
+        let entries: Vec<_> = handles(&buffer).unwrap().collect();
+
+        assert_eq!(
+            entries,
+            [
+                HandleEntry {
+                    value: 0x4,
+                    granted_access: 0x1f_0001,
+                    object_type_index: 37,
+                    attributes: 0,
+                },
+                HandleEntry {
+                    value: 0x1a8,
+                    granted_access: 0x1f_0001,
+                    object_type_index: 17,
+                    attributes: 0,
+                },
+            ]
+        );
+        assert_eq!(handles(&snapshot(&[])).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn rejects_count_beyond_buffer() {
+        let buffer = snapshot(&[(0x4, 37), (0x1a8, 17)]);
+
+        for length in 0..buffer.len() {
+            assert_eq!(
+                handles(&buffer[..length]).unwrap_err(),
+                "핸들 목록이 잘렸습니다.",
+                "length {length}"
+            );
+        }
+        // 곱하면 넘치는 개수
+        let mut huge = buffer.clone();
+        huge[..POINTER_SIZE].copy_from_slice(&usize::MAX.to_le_bytes());
+        assert!(handles(&huge).is_err());
+    }
+
+    #[test]
+    fn decodes_object_name_by_length() {
+        let name = "\\Sessions\\1\\BaseNamedObjects\\Starcraft Check For Other Instances";
+        let buffer = name_information(name);
+
+        assert_eq!(object_name(&buffer, BASE).unwrap(), name);
+        assert_eq!(object_name(&[0; 2 * POINTER_SIZE], BASE).unwrap(), "");
+
+        // 이름이 버퍼 밖을 가리키거나 잘림
+        assert!(object_name(&buffer, BASE + 0x100).is_err());
+        assert!(object_name(&buffer, BASE - 0x100).is_err());
+        assert!(object_name(&buffer[..buffer.len() - 8], BASE).is_err());
+        assert!(object_name(&buffer[..POINTER_SIZE], BASE).is_err());
+    }
+
+    #[test]
+    fn reads_object_types() {
+        let buffer = type_list(&[("Type", 2), ("Directory", 3), ("Mutant", 17)]);
+
+        let types = object_types(&buffer, BASE).unwrap();
+
+        assert_eq!(
+            types,
+            [
+                ObjectType {
+                    index: 2,
+                    name: "Type".to_string(),
+                },
+                ObjectType {
+                    index: 3,
+                    name: "Directory".to_string(),
+                },
+                ObjectType {
+                    index: 17,
+                    name: "Mutant".to_string(),
+                },
+            ]
+        );
+        // TypeIndex가 없는 예전 Windows
+        let legacy = type_list(&[("Type", 0), ("Directory", 0)]);
+        assert_eq!(object_types(&legacy, BASE).unwrap()[1].index, 3);
+        for length in 0..buffer.len() - 8 {
+            assert!(
+                object_types(&buffer[..length], BASE).is_err(),
+                "length {length}"
+            );
+        }
+    }
+
+    #[test]
+    fn default_game_only_scans_mutants() {
+        let buffer = type_list(&[("Type", 2), ("File", 37), ("Event", 16), ("Mutant", 17)]);
+        let types = object_types(&buffer, BASE).unwrap();
+        let events = GameDefinition {
+            lock_types: vec!["Mutant".to_string(), "Event".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(lock_type_indexes(&types, &GameDefinition::default()), [17]);
+        assert_eq!(lock_type_indexes(&types, &events), [16, 17]);
+    }
+
+    #[test]
+    fn damaged_buffers_never_panic() {
+        let snapshot = snapshot(&[(0x4, 37), (0x1a8, 17), (0x1ac, 12)]);
+        let name = name_information("\\BaseNamedObjects\\Starcraft Check For Other Instances");
+        let types = type_list(&[("Type", 2), ("Event", 16), ("Mutant", 17)]);
+
+        for buffer in [&snapshot, &name, &types] {
+            for damaged in damage::damaged(buffer) {
+                let _ = handles(&damaged).map(Iterator::count);
+                let _ = object_name(&damaged, BASE);
+                let _ = object_types(&damaged, BASE);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! NT API가 채운 버퍼에서 값 읽기
+//!
+//! 구조체 포인터로 바꾸지 않고 바이트 단위로 읽는다. 고정 크기 값은 호출하는 쪽에서 구조체
+//! 크기만큼 잘라 둔 조각에서만 읽는다.
+
+use std::mem::size_of;
+
+/// 포인터와 `HANDLE`의 크기
+pub const POINTER_SIZE: usize = size_of::<usize>();
+
+/// `offset`의 `u16`
+pub fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+/// `offset`의 `u32`
+pub fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// `offset`의 `u64`
+pub fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// `offset`의 포인터 크기 값
+pub fn read_usize(bytes: &[u8], offset: usize) -> usize {
+    usize::from_le_bytes(bytes[offset..offset + POINTER_SIZE].try_into().unwrap())
+}
+
+/// `offset`의 `UNICODE_STRING`이 가리키는 문자열
+///
+/// NT API는 문자열을 같은 버퍼 안에 쓰므로, `Buffer` 포인터에서 `buffer`의 주소 `base`를 빼
+/// 위치를 구한다. NUL을 찾지 않고 `Length`만큼 읽으며, 버퍼를 벗어나면 `None`이다.
+pub fn unicode_string(buffer: &[u8], base: usize, offset: usize) -> Option<String> {
+    let header = buffer.get(offset..offset.checked_add(2 * POINTER_SIZE)?)?;
+    let length = usize::from(read_u16(header, 0));
+    if length == 0 {
+        return Some(String::new());
+    }
+    if length % 2 != 0 {
+        return None;
+    }
+
+    let start = read_usize(header, POINTER_SIZE).checked_sub(base)?;
+    let units: Vec<u16> = buffer
+        .get(start..start.checked_add(length)?)?
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+
+    String::from_utf16(&units).ok()
+}
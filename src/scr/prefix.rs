@@ -0,0 +1,216 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io,
+    os::{fd::AsRawFd, unix::fs::symlink, unix::process::CommandExt},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::settings::WineSettings;
+
+// 복제하지 않고 원본을 가리키는 항목
+const SHARED_ENTRIES: [&str; 2] = ["drive_c", "dosdevices"];
+
+/// 인스턴스 prefix의 원본이 되는 prefix
+pub fn base_prefix(wine: &WineSettings) -> Option<PathBuf> {
+    if !wine.prefix.is_empty() {
+        return Some(PathBuf::from(&wine.prefix));
+    }
+
+    env::var_os("WINEPREFIX")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".wine")))
+}
+
+/// 인스턴스 prefix 하나를 쓰는 동안 잡아 두는 잠금, 버리면 풀림
+///
+/// 다른 런처나 CLI도 같은 폴더를 쓰므로 `<번호>.lock` 파일을 `flock`으로 잠근다.
+#[derive(Debug)]
+pub struct SlotLock(File);
+
+impl SlotLock {
+    /// 실행할 프로세스도 잠금을 물려받게 함
+    ///
+    /// 런처가 먼저 종료되어도 게임이 실행 중인 동안은 다른 런처가 prefix를 정리하지 않는다.
+    pub fn inherit(&self, command: &mut Command) {
+        let fd = self.0.as_raw_fd();
+        // fork와 exec 사이에서는 async-signal-safe인 fcntl만 호출
+        unsafe {
+            command.pre_exec(move || {
+                if libc::fcntl(fd, libc::F_SETFD, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+pub fn instances_dir(wine: &WineSettings) -> Option<PathBuf> {
+    if !wine.instances.is_empty() {
+        return Some(PathBuf::from(&wine.instances));
+    }
+
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|cache| cache.join("scr-multi-launcher").join("prefixes"))
+}
+
+/// `slot`번 인스턴스 prefix를 잠금, 다른 프로세스가 쓰고 있으면 `None`
+pub fn lock_slot(instances: &Path, slot: u32) -> io::Result<Option<SlotLock>> {
+    fs::create_dir_all(instances)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(instances.join(format!("{slot}.lock")))?;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let err = io::Error::last_os_error();
+        return match err.kind() {
+            io::ErrorKind::WouldBlock => Ok(None),
+            _ => Err(err),
+        };
+    }
+
+    Ok(Some(SlotLock(file)))
+}
+
+/// 비어 있는 가장 작은 번호의 인스턴스 prefix를 잠금
+pub fn acquire_slot(instances: &Path) -> io::Result<(u32, SlotLock)> {
+    let mut slot = 1;
+    loop {
+        if let Some(lock) = lock_slot(instances, slot)? {
+            return Ok((slot, lock));
+        }
+        slot += 1;
+    }
+}
+
+/// 잠겨 있지 않은 인스턴스 prefix와 그 잠금
+///
+/// 런처가 정리하기 전에 종료되어 남은 prefix이므로, 잠금을 쥔 채로 정리하면 된다.
+pub fn stale_slots(instances: &Path) -> Vec<(PathBuf, SlotLock)> {
+    let Ok(entries) = fs::read_dir(instances) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| {
+            let slot = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let lock = lock_slot(instances, slot).ok()??;
+
+            Some((entry.path(), lock))
+        })
+        .collect()
+}
+
+/// `base`를 `dest`에 복제
+///
+/// 레지스트리 등 wineserver가 쓰는 파일은 복사하고, `drive_c`와 `dosdevices`는
+/// 원본을 가리키는 심볼릭 링크로 만들어 게임 파일은 공유한다.
+pub fn clone_prefix(base: &Path, dest: &Path) -> io::Result<()> {
+    fs::create_dir_all(dest)?;
+
+    for entry in fs::read_dir(base)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let target = dest.join(&name);
+        let file_type = entry.file_type()?;
+
+        if SHARED_ENTRIES.iter().any(|shared| name == *shared) || file_type.is_symlink() {
+            symlink(fs::canonicalize(entry.path())?, target)?;
+        } else if file_type.is_dir() {
+            // Proton의 compatdata/pfx 처럼 prefix가 한 단계 안쪽에 있는 경우
+            clone_prefix(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_shares_game_files_and_copies_registry() {
+        let root = env::temp_dir().join(format!("scr-prefix-{}", std::process::id()));
+        let base = root.join("base");
+        fs::create_dir_all(base.join("drive_c/users")).unwrap();
+        fs::create_dir_all(base.join("dosdevices")).unwrap();
+        fs::write(base.join("system.reg"), "WINE REGISTRY").unwrap();
+        fs::create_dir_all(root.join("compat/pfx")).unwrap();
+        symlink(base.join("drive_c"), root.join("compat/pfx/drive_c")).unwrap();
+        fs::write(root.join("compat/version"), "9.0").unwrap();
+
+        let dest = root.join("instances/1");
+        clone_prefix(&base, &dest).unwrap();
+        clone_prefix(&root.join("compat"), &root.join("instances/2")).unwrap();
+
+        assert!(
+            fs::symlink_metadata(dest.join("drive_c"))
+                .unwrap()
+                .is_symlink()
+        );
+        assert!(
+            fs::symlink_metadata(dest.join("dosdevices"))
+                .unwrap()
+                .is_symlink()
+        );
+        assert!(dest.join("drive_c/users").is_dir());
+        fs::write(dest.join("system.reg"), "CHANGED").unwrap();
+        assert_eq!(
+            fs::read_to_string(base.join("system.reg")).unwrap(),
+            "WINE REGISTRY"
+        );
+        assert!(root.join("instances/2/pfx/drive_c/users").is_dir());
+        assert!(root.join("instances/2/version").is_file());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn slots_are_locked_until_dropped() {
+        let root = env::temp_dir().join(format!("scr-slots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let (first, first_lock) = acquire_slot(&root).unwrap();
+        let (second, _second_lock) = acquire_slot(&root).unwrap();
+        assert_eq!((first, second), (1, 2));
+        assert!(lock_slot(&root, 1).unwrap().is_none());
+        drop(first_lock);
+        let (reused, _reused_lock) = acquire_slot(&root).unwrap();
+        assert_eq!(reused, 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn only_unlocked_prefixes_are_stale() {
+        let root = env::temp_dir().join(format!("scr-stale-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for slot in ["1", "2", "other"] {
+            fs::create_dir_all(root.join(slot)).unwrap();
+        }
+        let _in_use = lock_slot(&root, 2).unwrap().unwrap();
+
+        let stale = stale_slots(&root);
+
+        assert_eq!(
+            stale.iter().map(|(dir, _)| dir).collect::<Vec<_>>(),
+            [&root.join("1")]
+        );
+        // 정리하는 동안은 새로 실행하는 클라이언트가 쓰지 못함
+        assert!(lock_slot(&root, 1).unwrap().is_none());
+        assert_eq!(acquire_slot(&root).unwrap().0, 3);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+//! `NtQuerySystemInformation(SystemProcessInformation)` 버퍼 읽기
+//!
+//! 버퍼는 `NextEntryOffset`으로 이어진 `SYSTEM_PROCESS_INFORMATION` 목록이다. 포인터로
+//! 바꾸지 않고 바이트 단위로 읽으며, 항목과 `ImageName`이 버퍼 안에 있는지 확인한다.
+//! 플랫폼과 무관한 처리라서 모든 OS에서 테스트한다.
+
+use crate::scr::nt_buffer::{POINTER_SIZE, read_u32, read_u64, read_usize, unicode_string};
+
+// SYSTEM_PROCESS_INFORMATION 필드 위치, 포인터 크기에 따라 ImageName 뒤가 달라짐
+const NEXT_ENTRY_OFFSET: usize = 0;
+const NUMBER_OF_THREADS: usize = 4;
+const CREATE_TIME: usize = 32;
+const IMAGE_NAME: usize = 56;
+const UNIQUE_PROCESS_ID: usize = (IMAGE_NAME + 2 * POINTER_SIZE + 4).next_multiple_of(POINTER_SIZE);
+const INHERITED_FROM_UNIQUE_PROCESS_ID: usize = UNIQUE_PROCESS_ID + POINTER_SIZE;
+// 읽는 필드를 모두 담는 항목의 최소 크기
+const ENTRY_SIZE: usize = INHERITED_FROM_UNIQUE_PROCESS_ID + POINTER_SIZE;
+
+/// 프로세스 목록의 항목 하나
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessRecord {
+    /// PID
+    pub pid: u32,
+    /// 부모 프로세스의 PID, 부모가 이미 종료했을 수 있음
+    pub parent_pid: u32,
+    /// 실행 파일 이름, System Idle Process는 빈 문자열
+    pub image_name: String,
+    /// 스레드 수
+    pub thread_count: u32,
+    /// FILETIME, `GetProcessTimes`의 생성 시각과 같은 값
+    pub create_time: u64,
+}
+
+/// `buffer`의 모든 프로세스
+///
+/// `base`는 `ImageName.Buffer` 포인터를 버퍼 안의 위치로 바꾸기 위한 `buffer`의 주소다.
+pub fn parse(buffer: &[u8], base: usize) -> Result<Vec<ProcessRecord>, String> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    loop {
+        let entry = buffer
+            .get(offset..)
+            .and_then(|rest| rest.get(..ENTRY_SIZE))
+            .ok_or_else(truncated)?;
+        records.push(ProcessRecord {
+            pid: read_pid(entry, UNIQUE_PROCESS_ID)?,
+            parent_pid: read_pid(entry, INHERITED_FROM_UNIQUE_PROCESS_ID)?,
+            image_name: unicode_string(buffer, base, offset + IMAGE_NAME).ok_or_else(malformed)?,
+            thread_count: read_u32(entry, NUMBER_OF_THREADS),
+            create_time: read_u64(entry, CREATE_TIME),
+        });
+
+        let next = read_u32(entry, NEXT_ENTRY_OFFSET) as usize;
+        if next == 0 {
+            return Ok(records);
+        }
+        // 다음 항목은 항상 현재 항목 뒤에 있으므로 목록이 돌지 않음
+        if next < ENTRY_SIZE {
+            return Err(malformed());
+        }
+        offset = offset.checked_add(next).ok_or_else(truncated)?;
+    }
+}
+
+fn read_pid(entry: &[u8], offset: usize) -> Result<u32, String> {
+    u32::try_from(read_usize(entry, offset)).map_err(|_| malformed())
+}
+
+fn truncated() -> String {
+    "프로세스 목록이 잘렸습니다.".to_string()
+}
+
+fn malformed() -> String {
+    "프로세스 목록 형식을 알 수 없습니다.".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scr::damage;
+
+    const BASE: usize = 0x10_0000;
+    // 실제 버퍼처럼 항목 뒤에 스레드 정보와 이름이 오도록 잡은 항목 크기
+    const STRIDE: usize = 0x180;
+
+    // (PID, 부모 PID, 이름) 목록으로 만든 버퍼
+    fn process_list(entries: &[(u32, u32, &str)]) -> Vec<u8> {
+        let mut buffer = vec![0u8; entries.len() * STRIDE];
+        for (index, (pid, parent, name)) in entries.iter().enumerate() {
+            let offset = index * STRIDE;
+            let entry = &mut buffer[offset..offset + STRIDE];
+            if index + 1 < entries.len() {
+                write(entry, NEXT_ENTRY_OFFSET, &(STRIDE as u32).to_le_bytes());
+            }
+            write(entry, NUMBER_OF_THREADS, &(index as u32 + 1).to_le_bytes());
+            write(entry, CREATE_TIME, &(1000 + *pid as u64).to_le_bytes());
+            write(entry, UNIQUE_PROCESS_ID, &(*pid as usize).to_le_bytes());
+            write(
+                entry,
+                INHERITED_FROM_UNIQUE_PROCESS_ID,
+                &(*parent as usize).to_le_bytes(),
+            );
+
+            let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            if !name.is_empty() {
+                let name_offset = ENTRY_SIZE + 0x40;
+                write(entry, name_offset, &name);
+                write(entry, IMAGE_NAME, &(name.len() as u16).to_le_bytes());
+                write(
+                    entry,
+                    IMAGE_NAME + 2,
+                    &(name.len() as u16 + 2).to_le_bytes(),
+                );
+                write(
+                    entry,
+                    IMAGE_NAME + POINTER_SIZE,
+                    &(BASE + offset + name_offset).to_le_bytes(),
+                );
+            }
+        }
+
+        buffer
+    }
+
+    fn write(entry: &mut [u8], offset: usize, bytes: &[u8]) {
+        entry[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn parses_process_list() {
+        let buffer = process_list(&[(0, 0, ""), (4, 0, "System"), (2000, 4, "StarCraft.exe")]);
+
+        let records = parse(&buffer, BASE).unwrap();
+
+        assert_eq!(
+            records,
+            [
+                ProcessRecord {
+                    pid: 0,
+                    parent_pid: 0,
+                    image_name: String::new(),
+                    thread_count: 1,
+                    create_time: 1000,
+                },
+                ProcessRecord {
+                    pid: 4,
+                    parent_pid: 0,
+                    image_name: "System".to_string(),
+                    thread_count: 2,
+                    create_time: 1004,
+                },
+                ProcessRecord {
+                    pid: 2000,
+                    parent_pid: 4,
+                    image_name: "StarCraft.exe".to_string(),
+                    thread_count: 3,
+                    create_time: 3000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_list() {
+        let buffer = process_list(&[(4, 0, "System"), (2000, 4, "StarCraft.exe")]);
+
+        // 다음 항목이 버퍼 밖에 있음
+        let mut past_end = buffer.clone();
+        write(
+            &mut past_end,
+            NEXT_ENTRY_OFFSET,
+            &(2 * STRIDE as u32).to_le_bytes(),
+        );
+        assert_eq!(
+            parse(&past_end, BASE).unwrap_err(),
+            "프로세스 목록이 잘렸습니다."
+        );
+
+        // 항목끼리 겹침
+        let mut overlapping = buffer.clone();
+        write(&mut overlapping, NEXT_ENTRY_OFFSET, &8u32.to_le_bytes());
+        assert_eq!(
+            parse(&overlapping, BASE).unwrap_err(),
+            "프로세스 목록 형식을 알 수 없습니다."
+        );
+
+        // 이름 길이가 UTF-16 단위로 나누어지지 않음
+        let mut odd_name = buffer.clone();
+        write(&mut odd_name, IMAGE_NAME, &3u16.to_le_bytes());
+        assert!(parse(&odd_name, BASE).is_err());
+
+        // 이름이 버퍼 앞이나 뒤에 있음
+        assert!(parse(&buffer, BASE + 0x1000).is_err());
+        assert!(parse(&buffer, BASE - buffer.len()).is_err());
+
+        assert!(parse(&buffer[..ENTRY_SIZE - 1], BASE).is_err());
+        assert!(parse(&[], BASE).is_err());
+    }
+
+    #[test]
+    fn truncated_lists_never_panic() {
+        let buffer = process_list(&[(4, 0, "System"), (2000, 4, "StarCraft.exe")]);
+
+        for length in 0..buffer.len() {
+            let result = parse(&buffer[..length], BASE);
+            // 두 번째 항목의 이름까지 남아 있으면 읽을 수 있음
+            if length < STRIDE + ENTRY_SIZE {
+                assert!(result.is_err(), "length {length}");
+            }
+        }
+    }
+
+    #[test]
+    fn damaged_lists_never_panic() {
+        let buffer = process_list(&[(0, 0, ""), (4, 0, "System"), (2000, 4, "StarCraft.exe")]);
+
+        for damaged in damage::damaged(&buffer).chain(damage::noise()) {
+            let _ = parse(&damaged, BASE);
+        }
+    }
+}
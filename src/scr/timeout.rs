@@ -0,0 +1,92 @@
+//! 멈출 수 있는 호출을 시간 제한 안에서 실행
+//!
+//! `NtQueryObject`는 일부 동기 파이프 핸들에서 돌아오지 않으므로 따로 만든 스레드에서 부르고,
+//! 제한 시간이 지나면 결과를 기다리지 않는다.
+
+use std::{sync::mpsc, thread, time::Duration};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// 호출을 차례로 실행하는 스레드 하나, 버리면 스레드도 끝남
+///
+/// 호출 하나가 제한 시간을 넘기면 스레드를 멈출 수 없으므로, 그 스레드는 호출이 끝날 때까지
+/// 남기고 이후의 호출은 실행하지 않는다.
+pub struct Worker {
+    // 시간을 넘긴 호출이 있거나 스레드를 만들지 못했으면 `None`
+    jobs: Option<mpsc::Sender<Job>>,
+}
+
+impl Worker {
+    pub fn new() -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let spawned = thread::Builder::new().spawn(move || {
+            for job in receiver {
+                job();
+            }
+        });
+
+        Self {
+            jobs: spawned.ok().map(|_| jobs),
+        }
+    }
+
+    /// 앞의 호출이 제한 시간을 넘겨 더 실행하지 않는지
+    pub fn is_stuck(&self) -> bool {
+        self.jobs.is_none()
+    }
+
+    /// `call`을 작업 스레드에서 실행하고 `timeout` 안에 끝나면 결과를 반환
+    pub fn run<T: Send + 'static>(
+        &mut self,
+        timeout: Duration,
+        call: impl FnOnce() -> T + Send + 'static,
+    ) -> Option<T> {
+        let (sender, receiver) = mpsc::channel();
+        self.jobs
+            .as_ref()?
+            .send(Box::new(move || {
+                // 기다리던 쪽이 포기했으면 보낼 곳이 없음
+                let _ = sender.send(call());
+            }))
+            .ok()?;
+
+        let result = receiver.recv_timeout(timeout).ok();
+        if result.is_none() {
+            self.jobs = None;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calls_share_one_thread() {
+        let mut worker = Worker::new();
+        let first = worker.run(Duration::from_secs(5), || thread::current().id());
+        let second = worker.run(Duration::from_secs(5), || thread::current().id());
+
+        assert!(first.is_some());
+        assert_eq!(first, second);
+        assert_ne!(first, Some(thread::current().id()));
+    }
+
+    #[test]
+    fn gives_up_after_blocked_call() {
+        let (release, blocked) = mpsc::channel::<()>();
+        let mut worker = Worker::new();
+
+        assert_eq!(worker.run(Duration::from_secs(5), || 42), Some(42));
+        assert_eq!(
+            worker.run(Duration::from_millis(20), move || blocked.recv().is_ok()),
+            None
+        );
+        // 멈춘 스레드 뒤에 호출을 쌓지 않음
+        assert!(worker.is_stuck());
+        assert_eq!(worker.run(Duration::from_secs(5), || 42), None);
+        drop(release);
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    ffi::OsStr,
+    fs::{self, OpenOptions},
+    os::{fd::AsRawFd, unix::ffi::OsStrExt},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::{SCRStruct, game::GameDefinition};
+
+const LOCK_FILE: &str = "scr-multi-launcher.lock";
+const PROC_ROOT: &str = "/proc";
+
+#[derive(Debug)]
+pub struct UnixProcess {
+    pub pid: u32,
+    // 열 때 읽은 생성 시각, PID가 재사용되었는지 확인하는 데 씀
+    pub start_time: Option<u64>,
+}
+
+impl UnixProcess {
+    fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            start_time: read_stat(&proc_dir(pid)).map(|stat| stat.start_time),
+        }
+    }
+
+    /// 아직 살아 있고 연 프로세스와 같은 프로세스인지
+    pub fn is_alive(&self) -> bool {
+        let is_alive = unsafe { libc::kill(self.pid as _, 0) } == 0;
+
+        is_alive && read_stat(&proc_dir(self.pid)).map(|stat| stat.start_time) == self.start_time
+    }
+}
+
+// /proc/<pid>/stat에서 쓰는 필드
+struct Stat {
+    parent_pid: u32,
+    start_time: u64,
+}
+
+/// 터미널에서 실행되므로 할 일이 없음
+pub fn attach_console() {}
+
+/// 런처가 이미 실행 중이면 `false`
+pub fn get_mutex() -> bool {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let Ok(file) = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE))
+    else {
+        return true;
+    };
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        return false;
+    }
+    // 프로그램이 끝날 때까지 잠금 유지
+    std::mem::forget(file);
+
+    true
+}
+
+/// `zenity`로 StarCraft.exe 경로를 고름
+pub fn get_path() -> Option<String> {
+    let output = Command::new("zenity")
+        .args([
+            "--file-selection",
+            "--title=스타크래프트 실행 파일 선택",
+            "--file-filter=StarCraft.exe | StarCraft.exe",
+        ])
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string()
+        })
+        .filter(|path| !path.is_empty())
+}
+
+pub fn process_handles(game: &GameDefinition) -> Vec<SCRStruct> {
+    scan_proc(Path::new(PROC_ROOT), game)
+}
+
+/// WINEPREFIX가 `prefix`인 클라이언트
+pub fn clients_in_prefix(game: &GameDefinition, prefix: &Path) -> Vec<SCRStruct> {
+    prefix_clients(Path::new(PROC_ROOT), game, prefix)
+}
+
+/// wine 프로세스면 Windows 실행 파일 이름, 아니면 `comm`
+pub fn process_name(pid: u32) -> Option<String> {
+    read_process_name(&proc_dir(pid))
+}
+
+fn proc_dir(pid: u32) -> PathBuf {
+    Path::new(PROC_ROOT).join(pid.to_string())
+}
+
+fn scan_proc(root: &Path, game: &GameDefinition) -> Vec<SCRStruct> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let cmdline = fs::read(entry.path().join("cmdline")).ok()?;
+            let exe = fs::read_link(entry.path().join("exe")).ok()?;
+
+            let stat = read_stat(&entry.path());
+
+            is_wine_client(&cmdline, &exe, game).then(|| SCRStruct {
+                parent_pid: stat.as_ref().map(|stat| stat.parent_pid),
+                created: stat.as_ref().map(|stat| stat.start_time),
+                ..SCRStruct::new(pid)
+            })
+        })
+        .collect()
+}
+
+fn prefix_clients(root: &Path, game: &GameDefinition, prefix: &Path) -> Vec<SCRStruct> {
+    scan_proc(root, game)
+        .into_iter()
+        .filter(|client| {
+            read_wineprefix(&root.join(client.pid.to_string())).is_some_and(|dir| dir == prefix)
+        })
+        .collect()
+}
+
+// wine은 새 프로세스를 두 번 fork해서 만들므로 부모를 따라가는 대신 환경 변수로 prefix를 찾음
+fn read_wineprefix(dir: &Path) -> Option<PathBuf> {
+    let environ = fs::read(dir.join("environ")).ok()?;
+
+    environ
+        .split(|&b| b == 0)
+        .find_map(|var| var.strip_prefix(b"WINEPREFIX="))
+        .map(|value| PathBuf::from(OsStr::from_bytes(value)))
+}
+
+// /proc/<pid>/stat의 4번째(ppid)와 22번째(starttime) 필드
+//
+// 2번째 필드인 이름에 공백과 괄호가 있을 수 있으므로 마지막 `)` 뒤의 3번째 필드부터 센다.
+fn read_stat(dir: &Path) -> Option<Stat> {
+    let stat = fs::read_to_string(dir.join("stat")).ok()?;
+    let (_, rest) = stat.rsplit_once(')')?;
+    let fields: Vec<_> = rest.split_whitespace().collect();
+
+    Some(Stat {
+        parent_pid: fields.get(1)?.parse().ok()?,
+        start_time: fields.get(19)?.parse().ok()?,
+    })
+}
+
+fn read_process_name(dir: &Path) -> Option<String> {
+    let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+    let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+    match std::str::from_utf8(argv0).map(file_name) {
+        Ok(name) if !name.is_empty() => Some(name.to_string()),
+        _ => fs::read_to_string(dir.join("comm"))
+            .ok()
+            .map(|comm| comm.trim_end().to_string()),
+    }
+}
+
+/// `/`와 `\` 구분자를 모두 쓰는 경로의 파일 이름
+pub fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+// wine이 실행한 Windows 프로세스는 argv[0]이 exe 경로이고 /proc/<pid>/exe는 wine 로더
+fn is_wine_client(cmdline: &[u8], exe: &Path, game: &GameDefinition) -> bool {
+    let is_wine = exe
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("wine"));
+    let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+
+    is_wine && std::str::from_utf8(argv0).is_ok_and(|argv0| game.is_client(file_name(argv0)))
+}
+
+pub fn get_process(pid: u32) -> Option<UnixProcess> {
+    let process = UnixProcess::new(pid);
+
+    process.is_alive().then_some(process)
+}
+
+/// 연 프로세스와 같은 프로세스일 때만 종료
+pub fn kill_process(process: &UnixProcess) -> bool {
+    process.is_alive() && unsafe { libc::kill(process.pid as _, libc::SIGKILL) == 0 }
+}
+
+pub fn spawn(command: Command) -> Option<(u32, UnixProcess)> {
+    spawn_with(command, || ())
+}
+
+/// 프로세스를 실행하고, 프로세스가 종료되거나 실행에 실패하면 `on_exit`를 호출
+pub fn spawn_with(
+    mut command: Command,
+    on_exit: impl FnOnce() + Send + 'static,
+) -> Option<(u32, UnixProcess)> {
+    let Ok(mut child) = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        on_exit();
+        return None;
+    };
+    let pid = child.id();
+    // 좀비 프로세스가 남지 않도록 종료를 기다림
+    std::thread::spawn(move || {
+        let _ = child.wait();
+        on_exit();
+    });
+
+    Some((pid, UnixProcess::new(pid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, os::unix::fs::symlink};
+
+    use super::*;
+
+    fn add_proc(root: &Path, pid: &str, cmdline: &[&str], exe: &str) {
+        let dir = root.join(pid);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("cmdline"), cmdline.join("\0") + "\0").unwrap();
+        symlink(exe, dir.join("exe")).unwrap();
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("scr-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("self")).unwrap();
+        root
+    }
+
+    #[test]
+    fn finds_wine_hosted_starcraft() {
+        let root = temp_root("proc");
+        add_proc(
+            &root,
+            "100",
+            &[
+                "C:\\Program Files (x86)\\StarCraft\\x86_64\\StarCraft.exe",
+                "-launch",
+            ],
+            "/usr/lib/wine/wine64-preloader",
+        );
+        add_proc(
+            &root,
+            "101",
+            &["Z:\\games\\StarCraft\\x86\\starcraft.exe"],
+            "/opt/proton/files/bin/wine-preloader",
+        );
+        // wine 실행 스크립트 자체는 제외
+        add_proc(
+            &root,
+            "102",
+            &[
+                "/usr/bin/wine",
+                "/games/StarCraft/x86_64/StarCraft.exe",
+                "-launch",
+            ],
+            "/usr/bin/bash",
+        );
+        add_proc(
+            &root,
+            "103",
+            &["C:\\windows\\system32\\services.exe"],
+            "/usr/lib/wine/wine64-preloader",
+        );
+        add_proc(
+            &root,
+            "104",
+            &["/usr/bin/StarCraft.exe"],
+            "/usr/bin/StarCraft.exe",
+        );
+
+        let mut found = scan_proc(&root, &GameDefinition::default());
+        found.sort();
+
+        assert_eq!(found, [SCRStruct::new(100), SCRStruct::new(101)]);
+        // 게임 정의의 실행 파일 이름으로 찾음
+        let services = GameDefinition {
+            image_names: vec!["services.exe".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(scan_proc(&root, &services), [SCRStruct::new(103)]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reads_parent_and_name() {
+        let root = temp_root("parent");
+        add_proc(
+            &root,
+            "200",
+            &["C:\\Program Files (x86)\\Battle.net\\Battle.net.exe"],
+            "/usr/lib/wine/wine64-preloader",
+        );
+        add_proc(
+            &root,
+            "201",
+            &["C:\\Program Files (x86)\\StarCraft\\x86_64\\StarCraft.exe"],
+            "/usr/lib/wine/wine64-preloader",
+        );
+        // 이름에 공백과 괄호가 있어도 부모 PID와 생성 시각을 찾음
+        let stat = format!("201 (StarCraft.exe) (x) S 200{} 4242 0", " 0".repeat(17));
+        fs::write(root.join("201/stat"), stat).unwrap();
+        fs::create_dir_all(root.join("202")).unwrap();
+        fs::write(root.join("202/comm"), "wineserver\n").unwrap();
+
+        let found = scan_proc(&root, &GameDefinition::default());
+        let name = |pid: &str| read_process_name(&root.join(pid));
+
+        assert_eq!(found[0].parent_pid, Some(200));
+        assert_eq!(found[0].created, Some(4242));
+        assert_eq!(name("200").as_deref(), Some("Battle.net.exe"));
+        assert_eq!(name("202").as_deref(), Some("wineserver"));
+        assert_eq!(name("203"), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn finds_clients_by_wineprefix() {
+        let root = temp_root("wineprefix");
+        for pid in ["300", "301", "302"] {
+            add_proc(
+                &root,
+                pid,
+                &["C:\\StarCraft\\x86_64\\StarCraft.exe"],
+                "/opt/proton/files/bin/wine64-preloader",
+            );
+        }
+        fs::write(
+            root.join("300/environ"),
+            "HOME=/home/user\0WINEPREFIX=/prefixes/1/pfx/\0",
+        )
+        .unwrap();
+        fs::write(root.join("301/environ"), "WINEPREFIX=/prefixes/2/pfx\0").unwrap();
+
+        let found = prefix_clients(
+            &root,
+            &GameDefinition::default(),
+            Path::new("/prefixes/1/pfx"),
+        );
+
+        // 끝의 `/`는 같은 경로로 봄
+        assert_eq!(found, [SCRStruct::new(300)]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,412 @@
+#![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]
+
+use std::{env, ops::Deref, sync::OnceLock, time::Duration};
+
+use windows::{
+    Wdk::{
+        Foundation::{NtQueryObject, OBJECT_INFORMATION_CLASS},
+        System::{
+            SystemInformation::{NtQuerySystemInformation, SystemProcessInformation},
+            Threading::{NtQueryInformationProcess, ProcessHandleInformation},
+        },
+    },
+    Win32::{
+        Foundation::{
+            DUPLICATE_CLOSE_SOURCE, DUPLICATE_HANDLE_OPTIONS, DUPLICATE_SAME_ACCESS,
+            DuplicateHandle, ERROR_ALREADY_EXISTS, FILETIME, GetLastError, HANDLE,
+            STATUS_INFO_LENGTH_MISMATCH, STATUS_SUCCESS, WAIT_OBJECT_0,
+        },
+        System::{
+            Com::{CLSCTX_INPROC_SERVER, CoCreateInstance},
+            Console::{ATTACH_PARENT_PROCESS, AttachConsole},
+            Threading::{
+                CREATE_NEW_CONSOLE, CREATE_NO_WINDOW, CREATE_UNICODE_ENVIRONMENT, CreateMutexW,
+                CreateProcessW, GetCurrentProcess, GetExitCodeProcess, GetProcessTimes,
+                OpenProcess, PROCESS_ALL_ACCESS, PROCESS_INFORMATION, STARTUPINFOW,
+                TerminateProcess, WaitForSingleObject,
+            },
+        },
+        UI::Shell::{Common::COMDLG_FILTERSPEC, FileOpenDialog, IFileDialog, SIGDN_FILESYSPATH},
+    },
+    core::{HSTRING, Owned, PCWSTR, PWSTR, w},
+};
+
+use crate::{
+    APP_NAME, LaunchRequest, SCRStruct,
+    game::GameDefinition,
+    scr::{
+        ClosedLocks, Liveness, NamedHandle, ProcessBackend,
+        cmdline::command_line,
+        environment::{environment_block, merge_env},
+        handle_snapshot::{self, ObjectType},
+        process_list::{self, ProcessRecord},
+        timeout::Worker,
+    },
+};
+
+const ObjectNameInformation: OBJECT_INFORMATION_CLASS = OBJECT_INFORMATION_CLASS(1i32);
+const ObjectTypesInformation: OBJECT_INFORMATION_CLASS = OBJECT_INFORMATION_CLASS(3i32);
+
+// 핸들 하나의 이름을 기다리는 최대 시간
+const NAME_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+// 현재 프로세스로 복제한 핸들, 버리면 닫힘
+//
+// 커널 핸들은 어느 스레드에서나 쓸 수 있으므로 이름 조회 스레드로 넘길 수 있게 함
+struct DuplicatedHandle(Owned<HANDLE>);
+
+unsafe impl Send for DuplicatedHandle {}
+
+impl DuplicatedHandle {
+    // `process` 안의 핸들 값 `value`를 복제
+    fn new(
+        process: &ProcessHandle,
+        value: usize,
+        options: DUPLICATE_HANDLE_OPTIONS,
+    ) -> Option<Self> {
+        let mut duplicated = HANDLE::default();
+        unsafe {
+            DuplicateHandle(
+                **process,
+                HANDLE(value as _),
+                GetCurrentProcess(),
+                &mut duplicated,
+                0,
+                false,
+                options,
+            )
+            .ok()?;
+
+            Some(Self(Owned::new(duplicated)))
+        }
+    }
+
+    fn get(&self) -> HANDLE {
+        *self.0
+    }
+}
+
+/// 열어 둔 프로세스 핸들, 버리면 닫힘
+///
+/// 커널 핸들은 어느 스레드에서나 쓸 수 있으므로 프로세스를 확인하는 작업을 다른 스레드로 넘길 수 있게 함
+#[derive(Debug)]
+pub struct ProcessHandle(Owned<HANDLE>);
+
+unsafe impl Send for ProcessHandle {}
+
+impl Deref for ProcessHandle {
+    type Target = HANDLE;
+
+    fn deref(&self) -> &HANDLE {
+        &self.0
+    }
+}
+
+/// Win32/NT API를 직접 호출하는 백엔드
+#[derive(Debug, Clone, Default)]
+pub struct WindowsBackend {
+    game: GameDefinition,
+    closed_locks: ClosedLocks,
+}
+
+impl ProcessBackend for WindowsBackend {
+    type Handle = ProcessHandle;
+
+    fn game(&self) -> &GameDefinition {
+        &self.game
+    }
+
+    fn set_game(&mut self, game: GameDefinition) {
+        self.game = game;
+    }
+
+    fn closed_locks(&self) -> &ClosedLocks {
+        &self.closed_locks
+    }
+
+    fn enumerate(&self) -> Vec<SCRStruct> {
+        process_handles(&self.game)
+    }
+
+    fn process_name(&self, pid: u32) -> Option<String> {
+        system_processes()
+            .into_iter()
+            .find(|record| record.pid == pid)
+            .map(|record| record.image_name)
+    }
+
+    fn open(&self, pid: u32) -> Option<Self::Handle> {
+        get_owned_handle(pid)
+    }
+
+    fn liveness(&self, process: &Self::Handle) -> Liveness {
+        if unsafe { WaitForSingleObject(**process, 0) } != WAIT_OBJECT_0 {
+            return Liveness::Running;
+        }
+        let mut code = 0;
+
+        Liveness::Exited(
+            unsafe { GetExitCodeProcess(**process, &mut code) }
+                .ok()
+                .map(|_| code),
+        )
+    }
+
+    fn creation_time(&self, process: &Self::Handle) -> Option<u64> {
+        let mut created = FILETIME::default();
+        let mut exited = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        unsafe { GetProcessTimes(**process, &mut created, &mut exited, &mut kernel, &mut user) }
+            .ok()?;
+
+        Some((u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime))
+    }
+
+    fn scan_handles(&self, process: &Self::Handle) -> Vec<NamedHandle> {
+        named_handles(process, &self.game)
+    }
+
+    fn close_handle(&self, process: &Self::Handle, handle: &NamedHandle) -> bool {
+        // 대상 프로세스의 핸들은 복제하면서 닫히고, 복제본은 여기서 버리면 닫힘
+        DuplicatedHandle::new(process, handle.value, DUPLICATE_CLOSE_SOURCE).is_some()
+    }
+
+    fn spawn(&self, request: &LaunchRequest) -> Option<(u32, Self::Handle)> {
+        run_scr(request)
+    }
+
+    fn terminate(&self, process: &Self::Handle) -> bool {
+        unsafe { TerminateProcess(**process, 0) }.is_ok()
+    }
+}
+
+/// GUI 서브시스템으로 빌드되면 콘솔이 없으므로 부모 프로세스의 콘솔에 연결
+pub fn attach_console() {
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+/// 런처가 이미 실행 중이면 `false`
+pub fn get_mutex() -> bool {
+    // 런처가 끝날 때까지 뮤텍스를 유지하도록 핸들을 닫지 않음
+    unsafe {
+        CreateMutexW(None, false, PCWSTR(HSTRING::from(APP_NAME).as_ptr())).is_ok()
+            && GetLastError() != ERROR_ALREADY_EXISTS
+    }
+}
+
+/// 파일 선택 창으로 StarCraft.exe 경로를 고름
+pub fn get_path() -> Option<String> {
+    unsafe {
+        let dialog =
+            CoCreateInstance::<_, IFileDialog>(&FileOpenDialog, None, CLSCTX_INPROC_SERVER).ok()?;
+
+        dialog.SetTitle(w!("스타크래프트 실행 파일 선택")).ok()?;
+        dialog
+            .SetFileTypes(&[COMDLG_FILTERSPEC {
+                pszName: w!("StarCraft.exe"),
+                pszSpec: w!("StarCraft.exe"),
+            }])
+            .ok()?;
+        dialog.Show(None).ok()?;
+        dialog
+            .GetResult()
+            .and_then(|item| item.GetDisplayName(SIGDN_FILESYSPATH))
+            .ok()
+            .and_then(|item| item.to_string().ok().filter(|e| !e.is_empty()))
+    }
+}
+
+pub fn get_owned_handle(pid: u32) -> Option<ProcessHandle> {
+    let owned_handle = unsafe { Owned::new(OpenProcess(PROCESS_ALL_ACCESS, false, pid).ok()?) };
+
+    Some(ProcessHandle(owned_handle))
+}
+
+pub fn process_handles(game: &GameDefinition) -> Vec<SCRStruct> {
+    system_processes()
+        .into_iter()
+        .filter(|record| game.is_client(&record.image_name))
+        .map(|record| SCRStruct {
+            parent_pid: Some(record.parent_pid),
+            created: Some(record.create_time),
+            ..SCRStruct::new(record.pid)
+        })
+        .collect()
+}
+
+// 이름이 있는 모든 프로세스
+fn system_processes() -> Vec<ProcessRecord> {
+    let mut process_list_size: u32 = 0;
+    let mut processes = Vec::new();
+    loop {
+        let status = unsafe {
+            NtQuerySystemInformation(
+                SystemProcessInformation,
+                processes.as_mut_ptr() as _,
+                processes.len() as _,
+                &mut process_list_size,
+            )
+        };
+
+        match status {
+            STATUS_INFO_LENGTH_MISMATCH => processes.resize(process_list_size as _, 0u8),
+            STATUS_SUCCESS => break,
+            _ => return Vec::new(),
+        }
+    }
+
+    // ImageName.Buffer는 같은 버퍼 안을 가리킴
+    let base = processes.as_ptr() as usize;
+    match process_list::parse(&processes, base) {
+        Ok(records) => records
+            .into_iter()
+            .filter(|record| record.pid != 0 && !record.image_name.is_empty())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn named_handles(owned_handle: &ProcessHandle, game: &GameDefinition) -> Vec<NamedHandle> {
+    let mut buffer = Vec::new();
+    let mut size = 0;
+    loop {
+        let status = unsafe {
+            NtQueryInformationProcess(
+                **owned_handle,
+                ProcessHandleInformation,
+                buffer.as_mut_ptr() as *mut _,
+                buffer.len() as u32,
+                &mut size,
+            )
+        };
+
+        match status {
+            STATUS_INFO_LENGTH_MISMATCH => buffer.resize(size as _, 0u8),
+            STATUS_SUCCESS => break,
+            _ => return Vec::new(),
+        }
+    }
+
+    let Ok(handles) = handle_snapshot::handles(&buffer) else {
+        return Vec::new();
+    };
+    // 종류를 모르면 모든 핸들의 이름을 물음
+    let lock_types = object_types()
+        .map(|types| handle_snapshot::lock_type_indexes(types, game))
+        .filter(|indexes| !indexes.is_empty());
+    let mut worker = Worker::new();
+    handles
+        .filter(|entry| {
+            lock_types
+                .as_ref()
+                .is_none_or(|indexes| indexes.contains(&entry.object_type_index))
+        })
+        // 이름 조회가 멈추면 이 프로세스의 남은 핸들은 묻지 않음
+        .map_while(|entry| {
+            let name = (!worker.is_stuck())
+                .then(|| object_name(owned_handle, entry.value, &mut worker))?;
+            Some(
+                name.filter(|name| !name.is_empty())
+                    .map(|name| NamedHandle {
+                        value: entry.value,
+                        name,
+                    }),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+// 시스템의 객체 종류 목록, 부팅하는 동안 바뀌지 않으므로 한 번만 찾음
+fn object_types() -> Option<&'static [ObjectType]> {
+    static TYPES: OnceLock<Option<Vec<ObjectType>>> = OnceLock::new();
+
+    TYPES
+        .get_or_init(|| {
+            let buffer = query_object(None, ObjectTypesInformation)?;
+            handle_snapshot::object_types(&buffer, buffer.as_ptr() as usize).ok()
+        })
+        .as_deref()
+}
+
+// 대상 프로세스의 핸들 값 `value`를 복제해 `worker`에서 읽은 객체 이름
+fn object_name(owned_handle: &ProcessHandle, value: usize, worker: &mut Worker) -> Option<String> {
+    let duplicated = DuplicatedHandle::new(owned_handle, value, DUPLICATE_SAME_ACCESS)?;
+
+    // 시간이 지나 포기해도 복제본은 질의가 끝난 스레드가 닫음
+    worker
+        .run(NAME_QUERY_TIMEOUT, move || {
+            let buffer = query_object(Some(duplicated.get()), ObjectNameInformation)?;
+            // Name.Buffer는 같은 버퍼 안을 가리킴
+            handle_snapshot::object_name(&buffer, buffer.as_ptr() as usize).ok()
+        })
+        .flatten()
+}
+
+// 크기를 맞춰 가며 받은 `NtQueryObject` 결과
+fn query_object(handle: Option<HANDLE>, class: OBJECT_INFORMATION_CLASS) -> Option<Vec<u8>> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut size: u32 = 0;
+    loop {
+        let status = unsafe {
+            NtQueryObject(
+                handle,
+                class,
+                Some(buffer.as_mut_ptr() as *mut _),
+                buffer.len() as u32,
+                Some(&mut size),
+            )
+        };
+
+        match status {
+            STATUS_INFO_LENGTH_MISMATCH => buffer.resize(size as usize, 0u8),
+            STATUS_SUCCESS => return Some(buffer),
+            _ => return None,
+        }
+    }
+}
+
+pub fn run_scr(request: &LaunchRequest) -> Option<(u32, ProcessHandle)> {
+    let application = HSTRING::from(request.path.as_str());
+    // CreateProcessW가 명령줄 버퍼를 수정할 수 있으므로 직접 소유한 버퍼를 넘김
+    let mut command_line: Vec<u16> = command_line(&request.path, &request.args)?
+        .encode_utf16()
+        .chain(Some(0))
+        .collect();
+    let current_dir = request.current_dir().map(HSTRING::from);
+    let launcher_env = env::vars_os().map(|(name, value)| {
+        (
+            name.to_string_lossy().into_owned(),
+            value.to_string_lossy().into_owned(),
+        )
+    });
+    let environment = environment_block(&merge_env(launcher_env, &request.env));
+    let mut process_info = PROCESS_INFORMATION::default();
+    let startup_info = STARTUPINFOW::default();
+
+    let (owned_handle, _thread) = unsafe {
+        CreateProcessW(
+            PCWSTR(application.as_ptr()),
+            Some(PWSTR(command_line.as_mut_ptr())),
+            None,
+            None,
+            false,
+            CREATE_NO_WINDOW | CREATE_NEW_CONSOLE | CREATE_UNICODE_ENVIRONMENT,
+            Some(environment.as_ptr() as _),
+            current_dir
+                .as_ref()
+                .map_or(PCWSTR::null(), |dir| PCWSTR(dir.as_ptr())),
+            &startup_info,
+            &mut process_info,
+        )
+        .ok()?;
+        // 주 스레드 핸들은 쓰지 않으므로 반환할 때 닫힘
+        (
+            Owned::new(process_info.hProcess),
+            Owned::new(process_info.hThread),
+        )
+    };
+
+    Some((process_info.dwProcessId, ProcessHandle(owned_handle)))
+}
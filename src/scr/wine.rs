@@ -0,0 +1,639 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    LaunchRequest, SCRStruct,
+    game::GameDefinition,
+    scr::{
+        ClosedLocks, Liveness, NamedHandle, ProcessBackend,
+        prefix::{acquire_slot, base_prefix, clone_prefix, instances_dir, stale_slots},
+        unix::{
+            UnixProcess, clients_in_prefix, file_name, get_process, kill_process, process_handles,
+            process_name, spawn, spawn_with,
+        },
+    },
+    settings::{Isolation, Settings, WineSettings},
+};
+
+const DEFAULT_WINE: &str = "wine";
+// Proton이 클라이언트를 실행할 때까지 기다리는 시간
+const PROTON_START_TIMEOUT: Duration = Duration::from_secs(30);
+const PROTON_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// wine 또는 Proton으로 StarCraft.exe를 실행하는 백엔드
+#[derive(Debug, Clone, Default)]
+pub struct WineBackend {
+    wine: WineSettings,
+    game: GameDefinition,
+    closed_locks: ClosedLocks,
+}
+
+impl WineBackend {
+    fn instance(&self, prefix: &Path) -> WineSettings {
+        WineSettings {
+            prefix: prefix.display().to_string(),
+            ..self.wine.clone()
+        }
+    }
+
+    // 이전 런처가 정리하지 못하고 남긴 인스턴스 prefix를 정리
+    fn reclaim(&self) {
+        let Some(instances) = instances_dir(&self.wine) else {
+            return;
+        };
+
+        for (prefix, _lock) in stale_slots(&instances) {
+            remove_instance(&self.instance(&prefix));
+        }
+    }
+
+    // 복제한 prefix에서 실행하고, 종료되면 prefix를 정리
+    fn run_isolated(&self, request: &LaunchRequest) -> Option<(WineSettings, (u32, UnixProcess))> {
+        let base = base_prefix(&self.wine)?;
+        let instances = instances_dir(&self.wine)?;
+        let (slot, lock) = acquire_slot(&instances).ok()?;
+        let prefix = instances.join(slot.to_string());
+        let wine = self.instance(&prefix);
+        // 잠금을 얻었으므로 남아 있는 prefix는 정리되지 못한 이전 실행의 것
+        if prefix.exists() {
+            remove_instance(&wine);
+        }
+        let mut command = wine_command(&wine, request);
+        lock.inherit(&mut command);
+        let cleanup = move || {
+            remove_instance(&wine);
+            drop(lock);
+        };
+
+        if clone_prefix(&base, &prefix).is_err() {
+            cleanup();
+            return None;
+        }
+
+        spawn_with(command, cleanup).map(|spawned| (self.instance(&prefix), spawned))
+    }
+
+    // Proton은 `run`을 처리하는 스크립트가 클라이언트를 실행하므로 그 PID로는 클라이언트를 다룰 수 없음
+    fn runs_through_wrapper(&self, request: &LaunchRequest) -> bool {
+        is_proton(program(&self.wine)) && self.game.is_client(file_name(&request.path))
+    }
+
+    // 스크립트와 같은 prefix에 새로 나타난 클라이언트
+    //
+    // 클라이언트가 나타나기 전에 스크립트가 종료되면 실행에 실패한 것이고, 제한 시간 안에 나타나지
+    // 않으면 스크립트를 그대로 씀
+    fn wait_for_client(
+        &self,
+        wine: &WineSettings,
+        (pid, wrapper): (u32, UnixProcess),
+        known: &[SCRStruct],
+    ) -> Option<(u32, UnixProcess)> {
+        let wineprefix = wineprefix(wine);
+        let started = Instant::now();
+
+        while started.elapsed() < PROTON_START_TIMEOUT {
+            let client = clients_in_prefix(&self.game, &wineprefix)
+                .into_iter()
+                .filter(|client| {
+                    !known
+                        .iter()
+                        .any(|known| known.pid == client.pid && known.created == client.created)
+                })
+                .min_by_key(|client| client.created);
+            if let Some(process) = client.and_then(|client| get_process(client.pid)) {
+                return Some((process.pid, process));
+            }
+            if !wrapper.is_alive() {
+                return None;
+            }
+            thread::sleep(PROTON_POLL_INTERVAL);
+        }
+
+        Some((pid, wrapper))
+    }
+}
+
+impl ProcessBackend for WineBackend {
+    type Handle = UnixProcess;
+
+    fn configure(&mut self, settings: &Settings) {
+        self.wine = settings.wine.clone();
+        self.reclaim();
+    }
+
+    // 뮤텍스는 wineserver 안에 있으므로 여기서 닫을 수 없음
+    fn closes_mutex(&self) -> bool {
+        false
+    }
+
+    fn game(&self) -> &GameDefinition {
+        &self.game
+    }
+
+    fn set_game(&mut self, game: GameDefinition) {
+        self.game = game;
+    }
+
+    fn closed_locks(&self) -> &ClosedLocks {
+        &self.closed_locks
+    }
+
+    fn enumerate(&self) -> Vec<SCRStruct> {
+        process_handles(&self.game)
+    }
+
+    fn process_name(&self, pid: u32) -> Option<String> {
+        process_name(pid)
+    }
+
+    fn open(&self, pid: u32) -> Option<Self::Handle> {
+        get_process(pid)
+    }
+
+    // wine이 실행한 프로세스의 종료 코드는 wineserver만 앎
+    fn liveness(&self, process: &Self::Handle) -> Liveness {
+        if process.is_alive() {
+            Liveness::Running
+        } else {
+            Liveness::Exited(None)
+        }
+    }
+
+    fn creation_time(&self, process: &Self::Handle) -> Option<u64> {
+        process.start_time
+    }
+
+    fn scan_handles(&self, _process: &Self::Handle) -> Vec<NamedHandle> {
+        Vec::new()
+    }
+
+    fn close_handle(&self, _process: &Self::Handle, _handle: &NamedHandle) -> bool {
+        false
+    }
+
+    fn spawn(&self, request: &LaunchRequest) -> Option<(u32, Self::Handle)> {
+        // 이미 실행 중인 클라이언트는 새로 실행한 클라이언트가 아님
+        let known = self
+            .runs_through_wrapper(request)
+            .then(|| process_handles(&self.game));
+        let (wine, spawned) = match request.isolation.unwrap_or(self.wine.isolation) {
+            Isolation::Shared => (self.wine.clone(), run_wine(&self.wine, request)?),
+            Isolation::Prefix => self.run_isolated(request)?,
+        };
+
+        match known {
+            Some(known) => self.wait_for_client(&wine, spawned, &known),
+            None => Some(spawned),
+        }
+    }
+
+    fn terminate(&self, process: &Self::Handle) -> bool {
+        kill_process(process)
+    }
+}
+
+fn is_proton(program: &str) -> bool {
+    Path::new(program)
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case("proton"))
+}
+
+fn program(wine: &WineSettings) -> &str {
+    if wine.path.is_empty() {
+        DEFAULT_WINE
+    } else {
+        &wine.path
+    }
+}
+
+// wine이 보는 WINEPREFIX, Proton은 STEAM_COMPAT_DATA_PATH 안의 `pfx`
+fn wineprefix(wine: &WineSettings) -> PathBuf {
+    if is_proton(program(wine)) {
+        Path::new(&wine.prefix).join("pfx")
+    } else {
+        PathBuf::from(&wine.prefix)
+    }
+}
+
+// 인스턴스 prefix의 wineserver를 종료하고 prefix를 삭제
+fn remove_instance(wine: &WineSettings) {
+    let program = program(wine);
+    let wineserver = if is_proton(program) {
+        Path::new(program).with_file_name("files/bin/wineserver")
+    } else {
+        Path::new(program).with_file_name("wineserver")
+    };
+
+    let _ = Command::new(wineserver)
+        .arg("-k")
+        .env("WINEPREFIX", wineprefix(wine))
+        .status();
+    let _ = fs::remove_dir_all(&wine.prefix);
+}
+
+pub fn wine_command(wine: &WineSettings, request: &LaunchRequest) -> Command {
+    let program = program(wine);
+    let mut command = Command::new(program);
+    // wine이 쓰는 변수는 아래에서 다시 정하므로 프로필이 덮어쓰지 못함
+    command.envs(&request.env);
+
+    if is_proton(program) {
+        command.arg("run");
+        if !wine.prefix.is_empty() {
+            command.env("STEAM_COMPAT_DATA_PATH", &wine.prefix);
+        }
+        // Steam 밖에서 Proton을 실행할 때 필요한 경로
+        if env::var_os("STEAM_COMPAT_CLIENT_INSTALL_PATH").is_none()
+            && let Some(home) = env::var_os("HOME")
+        {
+            command.env(
+                "STEAM_COMPAT_CLIENT_INSTALL_PATH",
+                PathBuf::from(home).join(".steam/steam"),
+            );
+        }
+    } else if !wine.prefix.is_empty() {
+        command.env("WINEPREFIX", &wine.prefix);
+    }
+    command.arg(&request.path).args(&request.args);
+    // 경로 변환은 추정이므로 실제로 있는 폴더일 때만 사용
+    if let Some(dir) = request.current_dir().and_then(|dir| unix_path(wine, &dir))
+        && dir.is_dir()
+    {
+        command.current_dir(dir);
+    }
+
+    command
+}
+
+// `C:\...` 같은 Windows 경로는 prefix의 dosdevices를 거치는 Unix 경로로 바꿈
+fn unix_path(wine: &WineSettings, path: &str) -> Option<PathBuf> {
+    let mut parts = path.split(['\\', '/']);
+    let drive = parts.next()?;
+    let [letter, b':'] = drive.as_bytes() else {
+        return Some(PathBuf::from(path));
+    };
+
+    let mut prefix = base_prefix(wine)?;
+    if is_proton(program(wine)) {
+        prefix.push("pfx");
+    }
+    let mut unix = prefix
+        .join("dosdevices")
+        .join(format!("{}:", letter.to_ascii_lowercase() as char));
+    unix.extend(parts.filter(|part| !part.is_empty()));
+
+    Some(unix)
+}
+
+pub fn run_wine(wine: &WineSettings, request: &LaunchRequest) -> Option<(u32, UnixProcess)> {
+    spawn(wine_command(wine, request))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        os::unix::fs::{PermissionsExt, symlink},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::scr::prefix::lock_slot;
+
+    // 인자와 WINEPREFIX를 기록하고 잠드는 wine 대역
+    fn stub_wine(dir: &Path) -> PathBuf {
+        let script = dir.join("wine");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho \"$WINEPREFIX $*\" >> '{}'\nexec sleep 30\n",
+                dir.join("args").display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        script
+    }
+
+    // STEAM_COMPAT_DATA_PATH의 pfx에서 클라이언트를 실행하고 종료를 기다리는 Proton 대역
+    fn stub_proton(dir: &Path) -> PathBuf {
+        let preloader = dir.join("wine64-preloader");
+        fs::copy("/bin/sleep", &preloader).unwrap();
+        let script = dir.join("proton");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/bash\n\
+                 export WINEPREFIX=\"$STEAM_COMPAT_DATA_PATH/pfx/\"\n\
+                 (exec -a 'C:\\StarCraft\\x86_64\\StarCraft.exe' '{}' 30) &\n\
+                 wait\n",
+                preloader.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        script
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+        (0..100).any(|_| {
+            thread::sleep(Duration::from_millis(20));
+            condition()
+        })
+    }
+
+    #[test]
+    fn launches_through_configured_wine() {
+        let dir = env::temp_dir().join(format!("scr-wine-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut backend = WineBackend::default();
+        backend.configure(&Settings {
+            wine: WineSettings {
+                path: stub_wine(&dir).display().to_string(),
+                prefix: "/tmp/scr-prefix".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let (pid, process) = backend
+            .spawn(&LaunchRequest::new("C:\\StarCraft\\x86_64\\StarCraft.exe"))
+            .unwrap();
+
+        assert_eq!(process.pid, pid);
+        assert!(wait_for(|| dir.join("args").exists()));
+        assert_eq!(
+            fs::read_to_string(dir.join("args")).unwrap().trim_end(),
+            "/tmp/scr-prefix C:\\StarCraft\\x86_64\\StarCraft.exe -launch"
+        );
+        assert!(backend.open(pid).is_some());
+        assert!(backend.terminate(&process));
+        assert!(wait_for(|| backend.open(pid).is_none()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn isolated_instances_get_own_prefix() {
+        let dir = env::temp_dir().join(format!("scr-isolated-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let base = dir.join("base");
+        fs::create_dir_all(base.join("drive_c")).unwrap();
+        fs::write(base.join("system.reg"), "WINE REGISTRY").unwrap();
+        let mut backend = WineBackend::default();
+        backend.configure(&Settings {
+            wine: WineSettings {
+                path: stub_wine(&dir).display().to_string(),
+                prefix: base.display().to_string(),
+                isolation: Isolation::Prefix,
+                instances: dir.join("instances").display().to_string(),
+            },
+            ..Default::default()
+        });
+
+        let (_, first) = backend.spawn(&LaunchRequest::new("StarCraft.exe")).unwrap();
+        let (_, second) = backend.spawn(&LaunchRequest::new("StarCraft.exe")).unwrap();
+
+        assert!(wait_for(|| {
+            fs::read_to_string(dir.join("args")).is_ok_and(|args| args.lines().count() == 2)
+        }));
+        let mut prefixes = fs::read_to_string(dir.join("args"))
+            .unwrap()
+            .lines()
+            .map(|line| line.split(' ').next().unwrap().to_string())
+            .collect::<Vec<_>>();
+        prefixes.sort();
+        assert_eq!(
+            prefixes,
+            [1, 2].map(|slot| dir
+                .join("instances")
+                .join(slot.to_string())
+                .display()
+                .to_string())
+        );
+        assert!(dir.join("instances/1/drive_c").is_dir());
+        assert!(dir.join("instances/2/system.reg").is_file());
+
+        assert!(backend.terminate(&first));
+        assert!(backend.terminate(&second));
+        assert!(wait_for(
+            || !dir.join("instances/1").exists() && !dir.join("instances/2").exists()
+        ));
+        assert!(wait_for(|| {
+            lock_slot(&dir.join("instances"), 1).is_ok_and(|lock| lock.is_some())
+                && lock_slot(&dir.join("instances"), 2).is_ok_and(|lock| lock.is_some())
+        }));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn leftover_prefixes_are_reclaimed_unless_locked() {
+        let dir = env::temp_dir().join(format!("scr-reclaim-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let instances = dir.join("instances");
+        fs::create_dir_all(instances.join("1/drive_c")).unwrap();
+        fs::create_dir_all(instances.join("2/drive_c")).unwrap();
+        fs::create_dir_all(dir.join("base")).unwrap();
+        // 다른 런처가 실행한 클라이언트가 쓰는 중
+        let in_use = lock_slot(&instances, 2).unwrap().unwrap();
+        let mut backend = WineBackend::default();
+
+        backend.configure(&Settings {
+            wine: WineSettings {
+                path: stub_wine(&dir).display().to_string(),
+                prefix: dir.join("base").display().to_string(),
+                isolation: Isolation::Prefix,
+                instances: instances.display().to_string(),
+            },
+            ..Default::default()
+        });
+
+        assert!(!instances.join("1").exists());
+        assert!(instances.join("2/drive_c").is_dir());
+        let (pid, process) = backend.spawn(&LaunchRequest::new("StarCraft.exe")).unwrap();
+        assert!(wait_for(|| dir.join("args").exists()));
+        let (_, other) = backend.spawn(&LaunchRequest::new("StarCraft.exe")).unwrap();
+        assert!(wait_for(|| {
+            fs::read_to_string(dir.join("args")).is_ok_and(|args| args.lines().count() == 2)
+        }));
+        let args = fs::read_to_string(dir.join("args")).unwrap();
+        assert!(args.contains(&instances.join("1").display().to_string()));
+        assert!(args.contains(&instances.join("3").display().to_string()));
+        assert!(instances.join("2/drive_c").is_dir());
+        drop(in_use);
+        // 런처가 먼저 종료되어도 잠금이 풀리지 않도록 클라이언트가 물려받음
+        let fds = fs::read_dir(format!("/proc/{pid}/fd")).unwrap();
+        assert!(
+            fds.flatten()
+                .any(|fd| fs::read_link(fd.path())
+                    .is_ok_and(|path| path == instances.join("1.lock")))
+        );
+
+        assert!(backend.terminate(&process));
+        assert!(backend.terminate(&other));
+        assert!(wait_for(|| !instances.join("1").exists()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn proton_reports_client_instead_of_wrapper() {
+        let dir = env::temp_dir().join(format!("scr-proton-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut backend = WineBackend::default();
+        backend.configure(&Settings {
+            wine: WineSettings {
+                path: stub_proton(&dir).display().to_string(),
+                prefix: dir.join("compat").display().to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let (pid, process) = backend
+            .spawn(&LaunchRequest::new("C:\\StarCraft\\x86_64\\StarCraft.exe"))
+            .unwrap();
+
+        assert_eq!(process.pid, pid);
+        assert_eq!(backend.process_name(pid).as_deref(), Some("StarCraft.exe"));
+        assert!(backend.enumerate().iter().any(|client| client.pid == pid));
+        assert!(backend.terminate(&process));
+        assert!(wait_for(|| backend.open(pid).is_none()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn profile_isolation_overrides_wine_settings() {
+        let dir = env::temp_dir().join(format!("scr-profile-isolation-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let base = dir.join("base");
+        fs::create_dir_all(base.join("drive_c")).unwrap();
+        let mut backend = WineBackend::default();
+        backend.configure(&Settings {
+            wine: WineSettings {
+                path: stub_wine(&dir).display().to_string(),
+                prefix: base.display().to_string(),
+                isolation: Isolation::Shared,
+                instances: dir.join("instances").display().to_string(),
+            },
+            ..Default::default()
+        });
+
+        let (_, shared) = backend.spawn(&LaunchRequest::new("StarCraft.exe")).unwrap();
+        assert!(wait_for(|| dir.join("args").exists()));
+        let (_, isolated) = backend
+            .spawn(&LaunchRequest {
+                isolation: Some(Isolation::Prefix),
+                ..LaunchRequest::new("StarCraft.exe")
+            })
+            .unwrap();
+
+        assert!(wait_for(|| {
+            fs::read_to_string(dir.join("args")).is_ok_and(|args| args.lines().count() == 2)
+        }));
+        let prefixes = fs::read_to_string(dir.join("args"))
+            .unwrap()
+            .lines()
+            .map(|line| line.split(' ').next().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prefixes,
+            [
+                base.display().to_string(),
+                dir.join("instances/1").display().to_string()
+            ]
+        );
+
+        assert!(backend.terminate(&shared));
+        assert!(backend.terminate(&isolated));
+        assert!(wait_for(|| !dir.join("instances/1").exists()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn working_dir_and_env_follow_request() {
+        let dir = env::temp_dir().join(format!("scr-cwd-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let game = dir.join("drive_c/Program Files (x86)/StarCraft");
+        fs::create_dir_all(&game).unwrap();
+        fs::create_dir_all(dir.join("dosdevices")).unwrap();
+        symlink("../drive_c", dir.join("dosdevices/c:")).unwrap();
+        let wine = WineSettings {
+            prefix: dir.display().to_string(),
+            ..Default::default()
+        };
+        let mut request = LaunchRequest::new("C:\\Program Files (x86)\\StarCraft\\StarCraft.exe");
+        request
+            .env
+            .insert("WINEDEBUG".to_string(), "-all".to_string());
+        request
+            .env
+            .insert("WINEPREFIX".to_string(), "/elsewhere".to_string());
+
+        let command = wine_command(&wine, &request);
+        let env = |key| {
+            command
+                .get_envs()
+                .filter(|(name, _)| *name == key)
+                .last()
+                .and_then(|(_, value)| value)
+        };
+
+        assert_eq!(
+            command.get_current_dir(),
+            Some(
+                dir.join("dosdevices/c:/Program Files (x86)/StarCraft")
+                    .as_path()
+            )
+        );
+        assert!(command.get_current_dir().unwrap().is_dir());
+        assert_eq!(env("WINEDEBUG"), Some("-all".as_ref()));
+        assert_eq!(env("WINEPREFIX"), Some(dir.as_os_str()));
+
+        request.working_dir = Some(game.display().to_string());
+        assert_eq!(
+            wine_command(&wine, &request).get_current_dir(),
+            Some(game.as_path())
+        );
+        request.working_dir = Some("D:\\Missing".to_string());
+        assert_eq!(wine_command(&wine, &request).get_current_dir(), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn proton_uses_run_verb_and_compat_data() {
+        let wine = WineSettings {
+            path: "/opt/proton/proton".to_string(),
+            prefix: "/games/scr".to_string(),
+            ..Default::default()
+        };
+
+        let command = wine_command(&wine, &LaunchRequest::new("StarCraft.exe"));
+
+        assert_eq!(command.get_program(), "/opt/proton/proton");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["run", "StarCraft.exe", "-launch"]
+        );
+        assert!(
+            command
+                .get_envs()
+                .any(|(key, value)| key == "STEAM_COMPAT_DATA_PATH"
+                    && value == Some("/games/scr".as_ref()))
+        );
+    }
+}
@@ -1,14 +1,264 @@
+//! `conf.toml` 설정
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    LaunchRequest, battle_net,
+    fingerprint::Fingerprint,
+    template::{Placeholders, expand},
+};
+
 const CONFIG_PATH: &str = "./conf.toml";
 
+/// 런처 설정
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(from = "SettingsFile")]
 pub struct Settings {
-    pub path_32: String,
-    pub path_64: String,
+    /// 일괄 실행 시 뮤텍스를 닫은 뒤 다음 클라이언트까지 대기 시간(ms)
+    pub batch_delay: u64,
+    /// 실행 파일이 업데이트되면 사용자가 확인할 때까지 실행하지 않음
+    pub block_on_update: bool,
+    /// Battle.net.exe 경로, 비어 있으면 기본 설치 경로
+    pub battle_net: String,
+    /// 실행 프로필, 메인 화면에 순서대로 버튼이 생긴다
+    pub profiles: Vec<Profile>,
+    /// Linux 전용 wine 설정
+    pub wine: WineSettings,
+    /// 실행 파일 경로별 마지막으로 확인한 지문
+    pub fingerprints: BTreeMap<String, Fingerprint>,
+}
+
+// 예전 `path_32`/`path_64` 설정 파일도 읽기 위한 형식
+#[derive(Deserialize)]
+struct SettingsFile {
+    #[serde(default)]
+    path_32: String,
+    #[serde(default)]
+    path_64: String,
+    #[serde(default)]
+    batch_delay: u64,
+    #[serde(default)]
+    block_on_update: bool,
+    #[serde(default)]
+    battle_net: String,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+    #[serde(default)]
+    wine: WineSettings,
+    #[serde(default)]
+    fingerprints: BTreeMap<String, Fingerprint>,
+}
+
+impl From<SettingsFile> for Settings {
+    fn from(file: SettingsFile) -> Self {
+        let mut profiles = file.profiles;
+        if profiles.is_empty() {
+            profiles = [
+                ("32bit", Arch::X86, file.path_32),
+                ("64bit", Arch::X64, file.path_64),
+            ]
+            .into_iter()
+            .filter(|(_, _, path)| !path.is_empty())
+            .map(|(name, arch, path)| Profile {
+                arch: Some(arch),
+                ..Profile::new(name, path)
+            })
+            .collect();
+        }
+
+        Self {
+            batch_delay: file.batch_delay,
+            block_on_update: file.block_on_update,
+            battle_net: file.battle_net,
+            profiles,
+            wine: file.wine,
+            fingerprints: file.fingerprints,
+        }
+    }
+}
+
+/// 이름 붙은 실행 설정
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Profile {
+    /// 버튼과 명령줄 `--profile`에 쓰이는 이름
+    pub name: String,
+    /// StarCraft.exe 경로
+    pub path: String,
+    /// StarCraft.exe를 실행하는 방법
+    pub launcher: Launcher,
+    /// 실행 파일 아키텍처, 비어 있으면 경로의 `x86`/`x86_64` 폴더로 판단
+    pub arch: Option<Arch>,
+    /// 실행 인자, [`template`](crate::template)의 자리 표시자를 쓸 수 있음
+    pub args: Vec<String>,
+    /// 작업 디렉터리, 비어 있으면 실행 파일이 있는 폴더
+    pub working_dir: String,
+    /// 런처 환경 변수에 더하거나 덮어쓸 환경 변수
+    pub env: BTreeMap<String, String>,
+    /// 버튼 색상 (`#rrggbb`), 비어 있으면 기본 색상
+    pub color: String,
+    /// Linux에서 여러 클라이언트를 실행하는 방법, 비어 있으면 `[wine]`의 `isolation`
+    pub isolation: Option<Isolation>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            path: String::new(),
+            launcher: Launcher::Direct,
+            arch: None,
+            args: vec!["-launch".to_string()],
+            working_dir: String::new(),
+            env: BTreeMap::new(),
+            color: String::new(),
+            isolation: None,
+        }
+    }
+}
+
+impl Profile {
+    /// 기본 인자(`-launch`)로 `path`를 실행하는 프로필
+    pub fn new(name: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 설정된 아키텍처, 없으면 실행 파일이 있는 폴더 이름으로 추정
+    pub fn effective_arch(&self) -> Option<Arch> {
+        self.arch.or_else(|| {
+            let folder = Path::new(&self.path.replace('\\', "/"))
+                .parent()?
+                .file_name()?
+                .to_str()?
+                .to_ascii_lowercase();
+            match folder.as_str() {
+                "x86" => Some(Arch::X86),
+                "x86_64" => Some(Arch::X64),
+                _ => None,
+            }
+        })
+    }
+
+    /// 일괄 실행의 `slot`번째 클라이언트를 지금 실행하는 요청
+    pub fn request(&self, slot: u32) -> LaunchRequest {
+        self.request_at(slot, Local::now())
+    }
+
+    /// 자리 표시자의 실행 시각을 `timestamp`로 정한 [`request`](Self::request)
+    pub fn request_at(&self, slot: u32, timestamp: DateTime<Local>) -> LaunchRequest {
+        let values = Placeholders {
+            slot,
+            profile: &self.name,
+            arch: self.effective_arch(),
+            timestamp,
+        };
+
+        LaunchRequest {
+            path: self.path.clone(),
+            args: self.args.iter().map(|arg| expand(arg, &values)).collect(),
+            working_dir: Some(self.working_dir.clone()).filter(|dir| !dir.is_empty()),
+            env: self.env.clone(),
+            isolation: self.isolation,
+        }
+    }
+
+    /// Battle.net에 StarCraft 실행을 맡기는 요청, `battle_net`은 Battle.net.exe 경로
+    pub fn battle_net_request(&self, battle_net: &str) -> LaunchRequest {
+        LaunchRequest {
+            path: battle_net.to_string(),
+            args: vec![battle_net::LAUNCH_ARG.to_string()],
+            working_dir: None,
+            env: self.env.clone(),
+            isolation: self.isolation,
+        }
+    }
+
+    /// 실행할 수 있는 설정인지, Battle.net으로 실행하면 StarCraft.exe 경로가 없어도 됨
+    pub fn is_launchable(&self) -> bool {
+        self.launcher == Launcher::BattleNet || !self.path.is_empty()
+    }
+}
+
+/// StarCraft.exe를 실행하는 방법
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Launcher {
+    /// StarCraft.exe를 직접 실행
+    #[default]
+    Direct,
+    /// `Battle.net.exe --exec="launch S1"`으로 Battle.net을 거쳐 실행
+    BattleNet,
+}
+
+/// 실행 파일 아키텍처
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arch {
+    /// 32비트
+    #[serde(rename = "x86")]
+    X86,
+    /// 64비트
+    #[serde(rename = "x86_64")]
+    X64,
+}
+
+// StarCraft 설치 폴더 이름과 같음
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arch::X86 => write!(f, "x86"),
+            Arch::X64 => write!(f, "x86_64"),
+        }
+    }
+}
+
+/// Linux에서 StarCraft.exe를 실행할 wine/Proton 설정
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct WineSettings {
+    /// wine 또는 proton 실행 파일, 비어 있으면 PATH의 wine 사용
+    pub path: String,
+    /// WINEPREFIX, Proton인 경우 STEAM_COMPAT_DATA_PATH
+    pub prefix: String,
+    /// 여러 클라이언트를 실행하는 방법
+    pub isolation: Isolation,
+    /// 인스턴스별 prefix를 만들 디렉터리, 비어 있으면 캐시 디렉터리 사용
+    pub instances: String,
+}
+
+/// 여러 클라이언트를 wine에서 실행하는 방법
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Isolation {
+    /// 모든 클라이언트가 하나의 prefix와 wineserver를 공유
+    #[default]
+    Shared,
+    /// 클라이언트마다 prefix를 복제해 별도의 wineserver에서 실행
+    Prefix,
 }
 
 impl Settings {
+    /// 설정된 Battle.net.exe 경로, 비어 있으면 기본 설치 경로
+    pub fn battle_net_path(&self) -> &str {
+        if self.battle_net.is_empty() {
+            battle_net::DEFAULT_PATH
+        } else {
+            &self.battle_net
+        }
+    }
+
+    /// 이름이 `name`인 프로필
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// `conf.toml`을 읽고, 없거나 손상되었으면 `None`
     pub async fn load() -> Option<Self> {
         use tokio::{fs::File, io::AsyncReadExt};
 
@@ -19,6 +269,7 @@ impl Settings {
         toml::from_str(&buffer).ok()
     }
 
+    /// `conf.toml`에 저장
     pub async fn save(self) -> Result<Self, String> {
         use tokio::fs::write;
 
@@ -31,3 +282,142 @@ impl Settings {
         .map_err(|err| err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn legacy_paths_become_profiles() {
+        let settings: Settings = toml::from_str(
+            r#"
+            path_32 = ""
+            path_64 = "C:\\StarCraft\\x86_64\\StarCraft.exe"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings.profiles,
+            [Profile {
+                arch: Some(Arch::X64),
+                ..Profile::new("64bit", "C:\\StarCraft\\x86_64\\StarCraft.exe")
+            }]
+        );
+    }
+
+    #[test]
+    fn request_expands_placeholders() {
+        let mut profile = Profile::new("team", "C:\\StarCraft\\x86\\StarCraft.exe");
+        profile.args = [
+            "-launch",
+            "-name",
+            "{profile}{slot}",
+            "-log",
+            "{arch}-{timestamp}",
+        ]
+        .map(String::from)
+        .to_vec();
+        let timestamp = Local.with_ymd_and_hms(2025, 7, 1, 9, 30, 5).unwrap();
+
+        let request = profile.request_at(3, timestamp);
+
+        assert_eq!(request.path, profile.path);
+        assert_eq!(
+            request.args,
+            ["-launch", "-name", "team3", "-log", "x86-20250701-093005"]
+        );
+    }
+
+    #[test]
+    fn arch_falls_back_to_install_folder() {
+        let guess = |path| Profile::new("", path).effective_arch();
+
+        assert_eq!(
+            guess("C:\\StarCraft\\x86_64\\StarCraft.exe"),
+            Some(Arch::X64)
+        );
+        assert_eq!(guess("/games/StarCraft/X86/StarCraft.exe"), Some(Arch::X86));
+        assert_eq!(guess("D:\\StarCraft.exe"), None);
+        let explicit = Profile {
+            arch: Some(Arch::X86),
+            ..Profile::new("", "C:\\StarCraft\\x86_64\\StarCraft.exe")
+        };
+        assert_eq!(explicit.effective_arch(), Some(Arch::X86));
+    }
+
+    #[test]
+    fn profiles_round_trip() {
+        let mut profile = Profile::new("windowed", "C:\\StarCraft\\x86_64\\StarCraft.exe");
+        profile.args.push("-windowed".to_string());
+        profile.working_dir = "C:\\StarCraft".to_string();
+        profile.env.insert("LANG".to_string(), "koKR".to_string());
+        profile.color = "#3366cc".to_string();
+        profile.isolation = Some(Isolation::Prefix);
+        let settings = Settings {
+            batch_delay: 500,
+            profiles: vec![profile, Profile::new("plain", "StarCraft.exe")],
+            ..Default::default()
+        };
+
+        let loaded: Settings = toml::from_str(&toml::to_string_pretty(&settings).unwrap()).unwrap();
+
+        assert_eq!(loaded.batch_delay, 500);
+        assert_eq!(loaded.profiles, settings.profiles);
+        assert_eq!(loaded.profile("plain").unwrap().args, ["-launch"]);
+        assert_eq!(loaded.profile("plain").unwrap().request(1).isolation, None);
+        assert_eq!(
+            loaded.profile("windowed").unwrap().request(1).isolation,
+            Some(Isolation::Prefix)
+        );
+    }
+
+    #[test]
+    fn fingerprints_round_trip() {
+        let path = "C:\\StarCraft\\x86_64\\StarCraft.exe";
+        let fingerprint = Fingerprint {
+            size: 1024,
+            modified: 1_700_000_000,
+            sha256: "ab".repeat(32),
+            version: Some("1.23.10.13515".parse().unwrap()),
+        };
+        let settings = Settings {
+            block_on_update: true,
+            profiles: vec![Profile::new("64bit", path)],
+            fingerprints: BTreeMap::from([(path.to_string(), fingerprint.clone())]),
+            ..Default::default()
+        };
+
+        let text = toml::to_string_pretty(&settings).unwrap();
+        let loaded: Settings = toml::from_str(&text).unwrap();
+
+        assert!(text.contains("version = \"1.23.10.13515\""), "{text}");
+        assert!(loaded.block_on_update);
+        assert_eq!(loaded.fingerprints[path], fingerprint);
+    }
+
+    #[test]
+    fn battle_net_profile_launches_client() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [[profiles]]
+            name = "bnet"
+            launcher = "battle_net"
+            env = { LANG = "koKR" }
+            "#,
+        )
+        .unwrap();
+        let profile = settings.profile("bnet").unwrap();
+
+        let request = profile.battle_net_request(settings.battle_net_path());
+
+        assert_eq!(profile.launcher, Launcher::BattleNet);
+        assert!(profile.is_launchable());
+        assert!(!Profile::new("empty", "").is_launchable());
+        assert_eq!(request.path, battle_net::DEFAULT_PATH);
+        assert_eq!(request.args, ["--exec=launch S1"]);
+        assert_eq!(request.env, profile.env);
+    }
+}
@@ -0,0 +1,108 @@
+//! 실행 인자의 `{이름}` 자리 표시자
+//!
+//! `{slot}`, `{profile}`, `{arch}`, `{timestamp}`를 실행할 때의 값으로 바꾼다. `{{`와 `}}`는
+//! 중괄호 하나가 되고, 모르는 이름은 그대로 남는다.
+
+use chrono::{DateTime, Local};
+
+use crate::settings::Arch;
+
+/// 자리 표시자에 들어갈 값
+#[derive(Debug, Clone)]
+pub struct Placeholders<'a> {
+    /// 일괄 실행 안에서의 순서, 1부터 시작
+    pub slot: u32,
+    /// 프로필 이름
+    pub profile: &'a str,
+    /// 실행 파일 아키텍처, 모르면 빈 문자열
+    pub arch: Option<Arch>,
+    /// 실행 시각, `%Y%m%d-%H%M%S` 형식
+    pub timestamp: DateTime<Local>,
+}
+
+impl Placeholders<'_> {
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "slot" => Some(self.slot.to_string()),
+            "profile" => Some(self.profile.to_string()),
+            "arch" => Some(self.arch.map(|arch| arch.to_string()).unwrap_or_default()),
+            "timestamp" => Some(self.timestamp.format("%Y%m%d-%H%M%S").to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// `template`의 자리 표시자를 `values`로 바꿈
+pub fn expand(template: &str, values: &Placeholders<'_>) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            expanded.push_str(&rest[..1]);
+            rest = &rest[2..];
+        } else if rest.starts_with('{')
+            && let Some(end) = rest.find('}')
+            && let Some(value) = values.get(&rest[1..end])
+        {
+            expanded.push_str(&value);
+            rest = &rest[end + 1..];
+        } else {
+            expanded.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    expanded.push_str(rest);
+
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn values() -> Placeholders<'static> {
+        Placeholders {
+            slot: 2,
+            profile: "windowed",
+            arch: Some(Arch::X64),
+            timestamp: Local.with_ymd_and_hms(2025, 7, 1, 9, 30, 5).unwrap(),
+        }
+    }
+
+    #[test]
+    fn expands_known_placeholders() {
+        let cases = [
+            ("-launch", "-launch"),
+            ("{slot}", "2"),
+            ("{profile}-{slot}", "windowed-2"),
+            (
+                "C:\\logs\\{arch}\\{timestamp}.txt",
+                "C:\\logs\\x86_64\\20250701-093005.txt",
+            ),
+            ("{{slot}}", "{slot}"),
+            ("{unknown} {slot", "{unknown} {slot"),
+            ("}{slot}{", "}2{"),
+            ("{슬롯}{slot}", "{슬롯}2"),
+        ];
+
+        for (template, expected) in cases {
+            assert_eq!(expand(template, &values()), expected, "{template}");
+        }
+    }
+
+    #[test]
+    fn unknown_arch_is_empty() {
+        let values = Placeholders {
+            arch: None,
+            ..values()
+        };
+
+        assert_eq!(expand("[{arch}]", &values), "[]");
+    }
+}